@@ -25,6 +25,11 @@ impl MyApp {
 }
 
 impl eframe::App for MyApp {
+    fn raw_input_hook(&mut self, _ctx: &egui::Context, raw_input: &mut egui::RawInput) {
+        // Keep the focus in the text field while the keys are pressed.
+        self.keyboard.filter_input(raw_input);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         CentralPanel::default().show(ctx, |ui| {
             // Inject text events into Egui context. This function needs to be called before any widget is
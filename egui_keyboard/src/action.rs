@@ -0,0 +1,99 @@
+//! What the keys do when pressed, separately from how they look.
+
+use crate::layouts::Layer;
+use egui::Modifiers;
+use std::rc::Rc;
+
+/// What pressing a key does.
+#[derive(Clone)]
+pub enum KeyAction {
+    /// Type the text.
+    Text(String),
+    /// Press the key, e.g. to drive shortcuts or games.
+    Key {
+        key: egui::Key,
+        modifiers: Modifiers,
+    },
+    /// Switch to the layer.
+    Layer(Layer),
+    /// Switch to the layout with the given index among the ones registered with
+    /// [`crate::Keyboard::add_layout`].
+    Layout(usize),
+    /// Call the function, e.g. to trigger an action of the application.
+    Callback(Rc<dyn Fn()>),
+    /// Do nothing.
+    None,
+}
+
+impl std::fmt::Debug for KeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text(text) => f.debug_tuple("Text").field(text).finish(),
+            Self::Key { key, modifiers } => f
+                .debug_struct("Key")
+                .field("key", key)
+                .field("modifiers", modifiers)
+                .finish(),
+            Self::Layer(layer) => f.debug_tuple("Layer").field(layer).finish(),
+            Self::Layout(index) => f.debug_tuple("Layout").field(index).finish(),
+            Self::Callback(_) => f.write_str("Callback"),
+            Self::None => f.write_str("None"),
+        }
+    }
+}
+
+/// Turns what the keys do by default, which is typing text, into what the application needs, so
+/// the same layout can serve different purposes.
+#[derive(Clone, Default)]
+pub enum KeyMode {
+    /// Type text, like a regular keyboard.
+    #[default]
+    Text,
+    /// Press keys instead of typing text where egui has a matching key, e.g. `a` is pressed as
+    /// [`egui::Key::A`] and `A` as the same key with shift.
+    KeyEvents,
+    /// Map the actions with a custom function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui_keyboard::{KeyAction, KeyMode};
+    /// use std::rc::Rc;
+    ///
+    /// // Space submits the form instead of typing.
+    /// let mode = KeyMode::Custom(Rc::new(|action| match action {
+    ///     KeyAction::Text(text) if text == " " => KeyAction::Key {
+    ///         key: egui::Key::Enter,
+    ///         modifiers: egui::Modifiers::NONE,
+    ///     },
+    ///     action => action,
+    /// }));
+    /// ```
+    Custom(Rc<dyn Fn(KeyAction) -> KeyAction>),
+}
+
+impl KeyMode {
+    pub(crate) fn map(&self, action: KeyAction) -> KeyAction {
+        match self {
+            Self::Text => action,
+            Self::KeyEvents => match action {
+                KeyAction::Text(text) => key_of_text(&text)
+                    .map(|(key, modifiers)| KeyAction::Key { key, modifiers })
+                    .unwrap_or(KeyAction::Text(text)),
+                action => action,
+            },
+            Self::Custom(map) => map(action),
+        }
+    }
+}
+
+/// Key typing the `text`, if there is one.
+fn key_of_text(text: &str) -> Option<(egui::Key, Modifiers)> {
+    let key = egui::Key::from_name(text)?;
+    let modifiers = if text.chars().any(char::is_uppercase) {
+        Modifiers::SHIFT
+    } else {
+        Modifiers::NONE
+    };
+    Some((key, modifiers))
+}
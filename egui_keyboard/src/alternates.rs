@@ -0,0 +1,219 @@
+//! Popup offering the alternates of a key, e.g. accented letters, opened by a long press or
+//! a right click.
+
+use crate::{button, KeyAction, Keyboard};
+use egui::{
+    vec2, Align2, Area, Context, FontId, Id, Order, PointerButton, Pos2, Rect, RichText, Ui,
+};
+
+/// Size of the hint of the first alternate, relative to the height of the key.
+const HINT_SIZE_FRACTION: f32 = 0.25;
+
+/// Space between the hint and the corner of the key, relative to the height of the key.
+const HINT_MARGIN_FRACTION: f32 = 0.08;
+
+pub(crate) struct AlternatesPopup {
+    /// Key which opened the popup.
+    pub(crate) key: Id,
+    key_rect: Rect,
+    /// Labels of the options with what they do, e.g. the text of the key itself followed by
+    /// its alternates.
+    options: Vec<(String, KeyAction)>,
+    /// Option under the finger which still holds the key.
+    selected: Option<usize>,
+    /// Whether the finger which opened the popup still holds the key. Once it is lifted without
+    /// choosing an option, the popup stays open until an option is tapped or the user taps
+    /// elsewhere.
+    sliding: bool,
+    /// Pass in which the finger was lifted, its release must not close the popup.
+    lifted_pass: u64,
+}
+
+impl AlternatesPopup {
+    /// Popup typing one of the `options`.
+    pub(crate) fn new(key: Id, key_rect: Rect, options: Vec<String>) -> Self {
+        let options = options
+            .into_iter()
+            .map(|text| (text.clone(), KeyAction::Text(text)))
+            .collect();
+        Self::with_actions(key, key_rect, options)
+    }
+
+    /// Popup doing what the chosen option does, e.g. switching the layout.
+    pub(crate) fn with_actions(key: Id, key_rect: Rect, options: Vec<(String, KeyAction)>) -> Self {
+        Self {
+            key,
+            key_rect,
+            options,
+            selected: None,
+            sliding: true,
+            lifted_pass: 0,
+        }
+    }
+
+    /// Popup opened by a tap lifted in the `pass`, which waits for an option to be tapped.
+    pub(crate) fn lifted(mut self, pass: u64) -> Self {
+        self.sliding = false;
+        self.lifted_pass = pass;
+        self
+    }
+
+    pub(crate) fn sliding(&self) -> bool {
+        self.sliding
+    }
+
+    /// Rects of the options, in a row right above the key, kept within the `screen`.
+    fn option_rects(&self, screen: Rect) -> Vec<Rect> {
+        let size = self.key_rect.size();
+        let width = size.x * self.options.len() as f32;
+        let left = (self.key_rect.center().x - width / 2.)
+            .min(screen.right() - width)
+            .max(screen.left());
+        let top = (self.key_rect.top() - size.y * 1.1).max(screen.top());
+        (0..self.options.len())
+            .map(|i| Rect::from_min_size(Pos2::new(left + i as f32 * size.x, top), size))
+            .collect()
+    }
+
+    /// Option the finger sliding from the key points at. Lowering the finger below the key
+    /// chooses none of them.
+    fn option_at(&self, screen: Rect, pos: Pos2) -> Option<usize> {
+        if pos.y > self.key_rect.bottom() {
+            return None;
+        }
+        let rects = self.option_rects(screen);
+        rects
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                (a.center().x - pos.x)
+                    .abs()
+                    .total_cmp(&(b.center().x - pos.x).abs())
+            })
+            .map(|(i, _)| i)
+    }
+}
+
+impl Keyboard {
+    /// Follow the finger which opened the popup of the key with given `id`, and type the option
+    /// it is lifted on.
+    pub(crate) fn slide_over_alternates(&mut self, ctx: &Context, id: Id) {
+        let (down, pos) = self.primary_pointer(ctx);
+        let screen = ctx.screen_rect();
+        let Some(popup) = self
+            .alternates_popup
+            .as_mut()
+            .filter(|popup| popup.key == id && popup.sliding)
+        else {
+            return;
+        };
+
+        // Popup opened by a right click waits for an option to be clicked.
+        let secondary_clicked =
+            ctx.input(|input| input.pointer.button_clicked(PointerButton::Secondary));
+        if secondary_clicked && !down {
+            popup.sliding = false;
+            popup.lifted_pass = ctx.cumulative_pass_nr();
+            return;
+        }
+
+        popup.selected = pos.and_then(|pos| popup.option_at(screen, pos));
+        if down {
+            return;
+        }
+
+        popup.sliding = false;
+        popup.lifted_pass = ctx.cumulative_pass_nr();
+        if let Some(selected) = popup.selected {
+            self.choose_alternate(ctx, selected);
+        }
+    }
+
+    /// Show the popup, if open, and type the option which was tapped.
+    pub(crate) fn show_alternates_popup(&mut self, ctx: &Context) {
+        let Some(popup) = &self.alternates_popup else {
+            return;
+        };
+
+        let rects = popup.option_rects(ctx.screen_rect());
+        let hits = |pos: Pos2| rects.iter().position(|rect| rect.contains(pos));
+
+        if !popup.sliding && popup.lifted_pass != ctx.cumulative_pass_nr() {
+            // Taps on the keyboard might be taken out of the egui input, so the buttons of the
+            // popup would not see them.
+            if let Some((origin, pos)) = self.captured_tap() {
+                match hits(origin).filter(|i| hits(pos) == Some(*i)) {
+                    Some(i) => self.choose_alternate(ctx, i),
+                    None => self.alternates_popup = None,
+                }
+                return;
+            }
+            // Closed on the release, so the key under the finger does not get the tap once the
+            // popup is gone.
+            let released_elsewhere = ctx.input(|input| {
+                input.pointer.any_released()
+                    && input
+                        .pointer
+                        .latest_pos()
+                        .is_some_and(|pos| hits(pos).is_none())
+            });
+            if released_elsewhere {
+                self.alternates_popup = None;
+                return;
+            }
+        }
+
+        let text_style = self.style.text_style();
+        let font_size = self.style.font_size;
+        let mut chosen = None;
+        Area::new(Id::new("keyboard_alternates"))
+            .order(Order::Tooltip)
+            .fixed_pos(rects.first().map_or(Pos2::ZERO, |rect| rect.min))
+            .show(ctx, |ui| {
+                for (i, ((label, _), rect)) in popup.options.iter().zip(&rects).enumerate() {
+                    let mut text = RichText::new(label).text_style(text_style.clone());
+                    if let Some(size) = font_size {
+                        text = text.size(size);
+                    }
+                    let option = button(text, rect.size()).selected(popup.selected == Some(i));
+                    if ui.put(*rect, option).clicked() {
+                        chosen = Some(i);
+                    }
+                }
+            });
+
+        if let Some(i) = chosen {
+            self.choose_alternate(ctx, i);
+        } else if self
+            .alternates_popup
+            .as_ref()
+            .is_some_and(|popup| popup.sliding)
+        {
+            ctx.request_repaint();
+        }
+    }
+
+    /// Type the option with given index and close the popup.
+    fn choose_alternate(&mut self, ctx: &Context, index: usize) {
+        let Some(popup) = self.alternates_popup.take() else {
+            return;
+        };
+        if let Some((_, action)) = popup.options.get(index) {
+            self.perform([action.clone()]);
+        }
+        self.focus_back_to_input_widget(ctx);
+    }
+}
+
+/// Paint the `hint` of what a long press of the key in the `rect` offers in its top right corner,
+/// see [`crate::KeyboardStyle::alternate_hints`].
+pub(crate) fn paint_hint(ui: &Ui, rect: Rect, hint: &str) {
+    let margin = rect.height() * HINT_MARGIN_FRACTION;
+    ui.painter().text(
+        rect.right_top() + vec2(-margin, margin),
+        Align2::RIGHT_TOP,
+        hint,
+        FontId::proportional(rect.height() * HINT_SIZE_FRACTION),
+        ui.visuals().weak_text_color(),
+    );
+}
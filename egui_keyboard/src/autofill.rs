@@ -0,0 +1,75 @@
+//! Learning what the user types, so the application can offer it again, e.g. in forms.
+
+use crate::suggestions::current_word;
+use crate::{InputHint, Keyboard};
+use egui::Id;
+
+/// Store of the text typed through the keyboard, see [`Keyboard::autofill_store`]. Nothing
+/// typed into the widgets with [`InputHint::Password`] is reported.
+pub trait AutofillStore {
+    /// The `word` was finished in the widget with the `id`, e.g. by a space or a comma after it.
+    fn word_committed(&mut self, id: Id, word: &str);
+
+    /// The widget with the `id` was left with the `text` typed into it through the keyboard,
+    /// because another widget got the focus or the keyboard was hidden. The text starts over
+    /// when the caret is moved with the keys.
+    fn field_committed(&mut self, id: Id, text: &str);
+
+    /// Texts offered above the keys for the widget with the `id` while nothing is typed into it,
+    /// e.g. what was typed into it in the earlier sessions. Tapping one types it, like the ones
+    /// from [`Keyboard::offer_suggestion`].
+    fn suggest(&self, id: Id) -> Vec<String> {
+        let _ = id;
+        Vec::new()
+    }
+}
+
+impl Keyboard {
+    /// Report the words and the texts of the fields typed through the keyboard to the `store`,
+    /// so the application can offer them again, e.g. to fill in a form in a later session. The
+    /// texts suggested by the store are offered above the keys.
+    pub fn autofill_store(mut self, store: impl AutofillStore + 'static) -> Self {
+        self.autofill_store = Some(Box::new(store));
+        self
+    }
+
+    /// Report the last typed word as finished to the [`Keyboard::autofill_store`].
+    pub(crate) fn commit_word(&mut self) {
+        let word = current_word(&self.typed);
+        let id = self
+            .input_widget
+            .filter(|_| !word.is_empty() && !self.secure());
+        if let (Some(store), Some(id)) = (&mut self.autofill_store, id) {
+            store.word_committed(id, word);
+        }
+    }
+
+    /// Report the text typed into the widget which is being left to the
+    /// [`Keyboard::autofill_store`] and start over.
+    pub(crate) fn commit_field(&mut self) {
+        if !self.typed.is_empty() {
+            self.commit_word();
+            let id = self.input_widget.filter(|_| !self.secure());
+            if let (Some(store), Some(id)) = (&mut self.autofill_store, id) {
+                store.field_committed(id, &self.typed);
+            }
+        }
+        self.typed.clear();
+    }
+
+    /// Texts suggested by the [`Keyboard::autofill_store`] for the focused widget.
+    pub(crate) fn autofill_suggestions(&self) -> Vec<String> {
+        let id = self
+            .input_widget
+            .filter(|_| self.typed.is_empty() && !self.secure());
+        match (&self.autofill_store, id) {
+            (Some(store), Some(id)) => store.suggest(id),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether nothing typed into the focused widget may be reported.
+    fn secure(&self) -> bool {
+        self.focused_input_hint() == InputHint::Password
+    }
+}
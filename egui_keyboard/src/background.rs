@@ -0,0 +1,193 @@
+//! Looking the suggestions up in a background thread, so large dictionaries do not slow the
+//! typing down.
+
+use crate::{Keyboard, SuggestionProvider};
+use egui::Context;
+use std::collections::{HashSet, VecDeque};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+
+/// How many lookups are remembered.
+const CACHE_CAPACITY: usize = 256;
+
+type SharedProvider = Arc<dyn SuggestionProvider + Send + Sync>;
+
+/// Provider shared with the background thread, for the lookups done right away.
+struct Shared(SharedProvider);
+
+impl SuggestionProvider for Shared {
+    fn suggest(&self, text: &str) -> Vec<String> {
+        self.0.suggest(text)
+    }
+
+    fn is_word_known(&self, word: &str) -> bool {
+        self.0.is_word_known(word)
+    }
+
+    fn corrections(&self, word: &str) -> Vec<String> {
+        self.0.corrections(word)
+    }
+
+    fn detect_language(&self, text: &str) -> Option<String> {
+        self.0.detect_language(text)
+    }
+}
+
+/// Words looked up for the end of the typed text or for a misspelled word.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Lookup {
+    Suggestions(String),
+    Corrections(String),
+}
+
+impl Lookup {
+    fn run(&self, provider: &SharedProvider) -> Vec<String> {
+        match self {
+            Self::Suggestions(word) => provider.suggest(word),
+            Self::Corrections(word) => provider.corrections(word),
+        }
+    }
+
+    fn same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// Background thread doing the lookups, see [`Keyboard::background_suggestion_provider`].
+struct Worker {
+    requests: Sender<Lookup>,
+    /// Words found by the lookups, or none if they were skipped as outdated.
+    results: Receiver<(Lookup, Option<Vec<String>>)>,
+}
+
+impl Worker {
+    /// Start the thread, unless threads are not available, e.g. on the web.
+    fn spawn(provider: SharedProvider, ctx: Context) -> Option<Self> {
+        let (requests, thread_requests) = channel::<Lookup>();
+        let (thread_results, results) = channel();
+        std::thread::Builder::new()
+            .name("keyboard suggestions".to_owned())
+            .spawn(move || {
+                // Ends when the keyboard is dropped along with the sender of the requests.
+                while let Ok(lookup) = thread_requests.recv() {
+                    // Typing fast queues lookups which are outdated by the time they would
+                    // be done, only the latest one of each kind is worth doing.
+                    let batch: Vec<Lookup> = std::iter::once(lookup)
+                        .chain(thread_requests.try_iter())
+                        .collect();
+                    for (i, lookup) in batch.iter().enumerate() {
+                        let outdated = batch[i + 1..].iter().any(|newer| newer.same_kind(lookup));
+                        let words = (!outdated).then(|| lookup.run(&provider));
+                        if thread_results.send((lookup.clone(), words)).is_err() {
+                            return;
+                        }
+                    }
+                    ctx.request_repaint();
+                }
+            })
+            .ok()?;
+        Some(Self { requests, results })
+    }
+}
+
+/// Lookups done in the background and cached, see [`Keyboard::background_suggestion_provider`].
+pub(crate) struct BackgroundLookups {
+    provider: SharedProvider,
+    /// Thread doing the lookups, started with the first one. `None` inside if it could not be
+    /// started, the lookups are done right away then.
+    worker: Option<Option<Worker>>,
+    /// Results of the recent lookups, the most recently used last.
+    cache: VecDeque<(Lookup, Vec<String>)>,
+    /// Lookups sent to the thread which did not come back yet.
+    pending: HashSet<Lookup>,
+}
+
+impl BackgroundLookups {
+    /// Take the finished lookups and start the `wanted` ones which are not cached yet.
+    fn update(&mut self, ctx: &Context, wanted: Vec<Lookup>) {
+        let worker = self
+            .worker
+            .get_or_insert_with(|| Worker::spawn(self.provider.clone(), ctx.clone()));
+        let finished: Vec<_> = match worker {
+            Some(worker) => worker.results.try_iter().collect(),
+            None => Vec::new(),
+        };
+        for (lookup, words) in finished {
+            self.pending.remove(&lookup);
+            if let Some(words) = words {
+                self.insert(lookup, words);
+            }
+        }
+
+        for lookup in wanted {
+            if let Some(i) = self.cache.iter().position(|(cached, _)| *cached == lookup) {
+                let entry = self.cache.remove(i).expect("position is in the cache");
+                self.cache.push_back(entry);
+                continue;
+            }
+            match &self.worker {
+                Some(Some(worker)) => {
+                    if !self.pending.contains(&lookup)
+                        && worker.requests.send(lookup.clone()).is_ok()
+                    {
+                        self.pending.insert(lookup);
+                    }
+                }
+                _ => {
+                    let words = lookup.run(&self.provider);
+                    self.insert(lookup, words);
+                }
+            }
+        }
+    }
+
+    fn insert(&mut self, lookup: Lookup, words: Vec<String>) {
+        self.cache.retain(|(cached, _)| *cached != lookup);
+        self.cache.push_back((lookup, words));
+        if self.cache.len() > CACHE_CAPACITY {
+            self.cache.pop_front();
+        }
+    }
+
+    /// Words found by the `lookup`. While it is being done, the words of the last lookup of the
+    /// same kind are kept, so the suggestion bar does not flicker.
+    pub(crate) fn get(&self, lookup: &Lookup) -> Vec<String> {
+        let pending = self.pending.contains(lookup);
+        self.cache
+            .iter()
+            .rev()
+            .find(|(cached, _)| cached == lookup || pending && cached.same_kind(lookup))
+            .map(|(_, words)| words.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Keyboard {
+    /// Like [`Keyboard::suggestion_provider`], but the words are looked up in a background
+    /// thread and delivered in a later frame, so large dictionaries do not slow the typing down.
+    /// The results are cached by the text they were looked up for, so the `provider` gets only
+    /// the last few words instead of all the typed text. Where threads are not available,
+    /// e.g. on the web, the words are looked up right away, still cached.
+    pub fn background_suggestion_provider(
+        mut self,
+        provider: impl SuggestionProvider + Send + Sync + 'static,
+    ) -> Self {
+        let provider: SharedProvider = Arc::new(provider);
+        self.suggestion_provider = Some(Box::new(Shared(provider.clone())));
+        self.background_lookups = Some(BackgroundLookups {
+            provider,
+            worker: None,
+            cache: VecDeque::new(),
+            pending: HashSet::new(),
+        });
+        self
+    }
+
+    /// Take the words looked up in the background and start looking up the ones needed now.
+    pub(crate) fn update_background_lookups(&mut self, ctx: &Context) {
+        let wanted = self.wanted_lookups();
+        if let Some(lookups) = &mut self.background_lookups {
+            lookups.update(ctx, wanted);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//! Words never offered in the suggestion bar, e.g. profanity.
+
+use crate::suggestions::current_word;
+use crate::Keyboard;
+
+impl Keyboard {
+    /// Never offer the `words` as suggestions or corrections, e.g. profanity, unless the user
+    /// typed the word in full before. Replaces the words blocked so far, none by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui_keyboard::{FrequencyDictionary, Keyboard};
+    ///
+    /// let keyboard = Keyboard::default()
+    ///     .suggestion_provider(FrequencyDictionary::new([("duck", 10), ("dumb", 5)]))
+    ///     .blocked_words(["dumb"]);
+    /// assert_eq!(keyboard.config().blocked_words, ["dumb"]);
+    /// ```
+    pub fn blocked_words(mut self, words: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.blocked_words.clear();
+        self.block_words(words);
+        self
+    }
+
+    /// Add the `words` to the [`Keyboard::blocked_words`].
+    pub fn block_words(&mut self, words: impl IntoIterator<Item = impl Into<String>>) {
+        self.blocked_words
+            .extend(words.into_iter().map(|word| word.into().to_lowercase()));
+    }
+
+    /// Whether the `word` must not be offered.
+    pub(crate) fn is_word_blocked(&self, word: &str) -> bool {
+        let lowercase = word.to_lowercase();
+        self.blocked_words.contains(&lowercase) && !self.typed_blocked_words.contains(&lowercase)
+    }
+
+    /// Offer the blocked word which was just finished from now on, the user typed it in full.
+    pub(crate) fn allow_typed_word(&mut self) {
+        let word = current_word(&self.typed).to_lowercase();
+        if self.blocked_words.contains(&word) {
+            self.typed_blocked_words.insert(word);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Keys of the bottom row chosen by the application, whichever layout is shown.
+
+use crate::layouts::Row;
+use crate::{Key, KeyCategory, Keyboard};
+use egui::{Response, Ui, Vec2};
+
+/// Keys of the bottom row, the one with the space key, added to or removed from what the layout
+/// has there, see [`Keyboard::bottom_row`].
+///
+/// # Examples
+///
+/// ```
+/// use egui_keyboard::{BottomRowConfig, Keyboard};
+///
+/// let keyboard = Keyboard::default().bottom_row(BottomRowConfig {
+///     emoji: false,
+///     dismiss: true,
+///     ..Default::default()
+/// });
+/// assert!(keyboard.config().bottom_row.dismiss);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct BottomRowConfig {
+    /// Key switching to the emojis, shown when there are any, see [`Keyboard::emoji_dataset`].
+    pub emoji: bool,
+    /// Key switching the layouts, shown when there are several, see [`Keyboard::add_layout`].
+    pub globe: bool,
+    /// The comma and the period next to the space key.
+    pub punctuation: bool,
+    /// Key starting the dictation, shown when there is one, see [`Keyboard::on_dictation`].
+    pub dictation: bool,
+    /// Key hiding the keyboard by taking the focus from the input widget.
+    pub dismiss: bool,
+}
+
+impl Default for BottomRowConfig {
+    fn default() -> Self {
+        Self {
+            emoji: true,
+            globe: true,
+            punctuation: true,
+            dictation: true,
+            dismiss: false,
+        }
+    }
+}
+
+impl Keyboard {
+    /// Keys of the bottom row of all the layouts, e.g. without the emoji key or with a key
+    /// hiding the keyboard.
+    pub fn bottom_row(mut self, config: BottomRowConfig) -> Self {
+        self.bottom_row = config;
+        self
+    }
+
+    /// Change the keys of the bottom row, see [`Keyboard::bottom_row`].
+    pub fn set_bottom_row(&mut self, config: BottomRowConfig) {
+        self.bottom_row = config;
+    }
+
+    /// Show a key starting the dictation in the bottom row, calling the `callback` when it is
+    /// tapped. The application types the recognized text with [`Keyboard::insert_text`].
+    pub fn on_dictation(mut self, callback: impl FnMut() + 'static) -> Self {
+        self.on_dictation = Some(Box::new(callback));
+        self
+    }
+
+    /// Remove the keys of the layout which are turned off in the [`Keyboard::bottom_row`] from
+    /// the last of the `rows`.
+    pub(crate) fn tailor_bottom_row(&self, rows: &mut [Row]) {
+        let Some(row) = rows.last_mut() else {
+            return;
+        };
+        let config = self.bottom_row;
+        row.keys.retain(|key| match key.base() {
+            Key::EmojiLayer => config.emoji,
+            Key::Globe => config.globe,
+            Key::Text(text) if text == "," || text == "." => config.punctuation,
+            _ => true,
+        });
+    }
+
+    pub(crate) fn dictation_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) -> Response {
+        let response = self.add_key_button(ui, "🎤", false, KeyCategory::Action, button_size);
+        if self.interaction(ui, &response).clicked {
+            if let Some(callback) = &mut self.on_dictation {
+                callback();
+            }
+            self.focus_back_to_input_widget(ui.ctx());
+        }
+        response
+    }
+
+    pub(crate) fn dismiss_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) -> Response {
+        let response = self.add_key_button(ui, "⏷", false, KeyCategory::Action, button_size);
+        if self.interaction(ui, &response).clicked {
+            self.commit_field();
+            if let Some(widget) = self.input_widget.take() {
+                ui.ctx().memory_mut(|memory| memory.surrender_focus(widget));
+            }
+            self.needed = 0;
+        }
+        response
+    }
+}
@@ -0,0 +1,145 @@
+//! Evaluating the expression typed on the numeric keys.
+
+use crate::{key_event, InputHint, KeyCategory, Keyboard};
+use egui::{Context, Event, Modifiers, Response, Ui, Vec2};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Decimal places the result is rounded to.
+const RESULT_DECIMALS: usize = 10;
+
+/// Parser of arithmetic expressions with `+`, `-`, `*`, `/`, parentheses and numbers with
+/// either `.` or `,` as the decimal separator.
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    /// Next character which is not a whitespace.
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn expression(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        loop {
+            match self.peek() {
+                Some('+') => {
+                    self.chars.next();
+                    value += self.term()?;
+                }
+                Some('-' | '−') => {
+                    self.chars.next();
+                    value -= self.term()?;
+                }
+                _ => return Some(value),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.factor()?;
+        loop {
+            match self.peek() {
+                Some('*' | '×') => {
+                    self.chars.next();
+                    value *= self.factor()?;
+                }
+                Some('/' | '÷') => {
+                    self.chars.next();
+                    value /= self.factor()?;
+                }
+                _ => return Some(value),
+            }
+        }
+    }
+
+    fn factor(&mut self) -> Option<f64> {
+        match self.peek()? {
+            '-' | '−' => {
+                self.chars.next();
+                Some(-self.factor()?)
+            }
+            '(' => {
+                self.chars.next();
+                let value = self.expression()?;
+                (self.peek() == Some(')')).then(|| self.chars.next())?;
+                Some(value)
+            }
+            _ => self.number(),
+        }
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        let mut number = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '.' | ','))
+        {
+            number.push(if c == ',' { '.' } else { c });
+        }
+        number.parse().ok()
+    }
+}
+
+/// Value of the arithmetic `expression`, if it is a complete one.
+fn evaluate(expression: &str) -> Option<f64> {
+    let mut parser = Parser {
+        chars: expression.chars().peekable(),
+    };
+    let value = parser.expression()?;
+    (parser.peek().is_none() && value.is_finite()).then_some(value)
+}
+
+/// The `value` rounded, without trailing zeros.
+fn format_result(value: f64) -> String {
+    let text = format!("{value:.RESULT_DECIMALS$}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_owned(),
+        text => text.to_owned(),
+    }
+}
+
+impl Keyboard {
+    /// Evaluate the expression typed on the numeric keys, see [`InputHint::Numeric`]. The keys
+    /// get the operators and the parentheses, the result is shown above them as it is typed and
+    /// the `=` key replaces the expression with it, e.g. to add up the prices in a point of sale.
+    ///
+    /// Only the expression typed through the keyboard since the field got focus or the caret
+    /// last moved is evaluated.
+    pub fn calculator(mut self, enabled: bool) -> Self {
+        self.calculator = enabled;
+        self
+    }
+
+    /// Result of the typed expression, if it is a complete one which is not just a number.
+    pub(crate) fn calculation(&self) -> Option<String> {
+        if !self.calculator || self.focused_input_hint() != InputHint::Numeric {
+            return None;
+        }
+        let expression = self.digits.delocalize(&self.typed);
+        let result = format_result(evaluate(&expression)?);
+        (result != expression.trim()).then(|| self.digits.localize(&result).into_owned())
+    }
+
+    /// Replace the typed expression with its result.
+    pub(crate) fn accept_calculation(&mut self, ctx: &Context) {
+        let Some(result) = self.calculation() else {
+            return;
+        };
+        let backspaces = self.typed.chars().count();
+        let backspace = key_event(egui::Key::Backspace, Modifiers::NONE);
+        self.push_events(std::iter::repeat_n(backspace, backspaces).chain([Event::Text(result)]));
+        self.focus_back_to_input_widget(ctx);
+    }
+
+    pub(crate) fn equals_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) -> Response {
+        let response = self.add_key_button(ui, "=", false, KeyCategory::Action, button_size);
+        if self.interaction(ui, &response).clicked {
+            self.accept_calculation(ui.ctx());
+        }
+        response
+    }
+}
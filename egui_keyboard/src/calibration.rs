@@ -0,0 +1,135 @@
+//! Measuring how far the touches land from where they are aimed, e.g. on touch panels with
+//! parallax between the glass and the display.
+
+use crate::Keyboard;
+use egui::{
+    vec2, Align2, Area, Color32, Context, Event, FontId, Id, Order, PointerButton, Sense, Stroke,
+    Vec2,
+};
+
+/// Targets tapped during the calibration, relative to the screen.
+const TARGETS: [Vec2; 5] = [
+    vec2(0.2, 0.2),
+    vec2(0.8, 0.2),
+    vec2(0.5, 0.5),
+    vec2(0.2, 0.8),
+    vec2(0.8, 0.8),
+];
+
+const TARGET_RADIUS: f32 = 20.0;
+
+const TARGET_WIDTH: f32 = 2.0;
+
+/// Taps landing farther from the target count as landing this far, so that a few stray taps do
+/// not throw the calibration off.
+const MAX_OFFSET: f32 = 3.0 * TARGET_RADIUS;
+
+/// Calibration in progress, see [`Keyboard::start_touch_calibration`].
+#[derive(Default)]
+pub(crate) struct Calibration {
+    /// How far the taps of the targets tapped so far landed from them.
+    offsets: Vec<Vec2>,
+}
+
+impl Keyboard {
+    /// Move the presses on the keys by the `offset`, e.g. as measured by
+    /// [`Keyboard::start_touch_calibration`]. Applies to the presses taken out of the egui input
+    /// by [`Keyboard::filter_input`].
+    pub fn touch_offset(mut self, offset: Vec2) -> Self {
+        self.touch_offset = offset;
+        self
+    }
+
+    /// Ask the user to tap five targets over the screen, one after another, and measure how far
+    /// from them the taps land on average. The result is applied to the presses on the keys
+    /// right away, and can be stored as [`crate::KeyboardConfig::touch_offset`].
+    pub fn start_touch_calibration(&mut self) {
+        self.calibration = Some(Calibration::default());
+    }
+
+    /// Whether the user is tapping the targets of [`Keyboard::start_touch_calibration`].
+    pub fn is_calibrating_touch(&self) -> bool {
+        self.calibration.is_some()
+    }
+
+    /// Show the next target over everything else and measure the taps of it.
+    pub(crate) fn show_calibration(&mut self, ctx: &Context) {
+        let Some(calibration) = &mut self.calibration else {
+            return;
+        };
+        let screen = ctx.screen_rect();
+        let taps: Vec<_> = ctx.input(|input| {
+            input
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::PointerButton {
+                        pos,
+                        button: PointerButton::Primary,
+                        pressed: true,
+                        ..
+                    } => Some(*pos),
+                    _ => None,
+                })
+                .collect()
+        });
+        for tap in taps {
+            let Some(target) = TARGETS.get(calibration.offsets.len()) else {
+                break;
+            };
+            let offset = screen.min + *target * screen.size() - tap;
+            calibration
+                .offsets
+                .push(offset.normalized() * offset.length().min(MAX_OFFSET));
+        }
+
+        let Some(target) = TARGETS.get(calibration.offsets.len()) else {
+            let sum = calibration
+                .offsets
+                .iter()
+                .fold(Vec2::ZERO, |sum, offset| sum + *offset);
+            self.touch_offset = sum / TARGETS.len() as f32;
+            self.calibration = None;
+            return;
+        };
+        let target = screen.min + *target * screen.size();
+        let progress = format!(
+            "Tap the target {}/{}",
+            calibration.offsets.len() + 1,
+            TARGETS.len()
+        );
+        Area::new(Id::new("keyboard_calibration"))
+            .order(Order::Tooltip)
+            .fixed_pos(screen.min)
+            .show(ctx, |ui| {
+                // Takes the taps, so nothing under the targets reacts to them.
+                ui.allocate_rect(screen, Sense::click());
+                let painter = ui.painter();
+                let visuals = ui.visuals();
+                painter.rect_filled(screen, 0.0, Color32::from_black_alpha(200));
+                let stroke = Stroke::new(TARGET_WIDTH, visuals.strong_text_color());
+                painter.circle_stroke(target, TARGET_RADIUS, stroke);
+                painter.line_segment(
+                    [
+                        target - vec2(TARGET_RADIUS, 0.0),
+                        target + vec2(TARGET_RADIUS, 0.0),
+                    ],
+                    stroke,
+                );
+                painter.line_segment(
+                    [
+                        target - vec2(0.0, TARGET_RADIUS),
+                        target + vec2(0.0, TARGET_RADIUS),
+                    ],
+                    stroke,
+                );
+                painter.text(
+                    screen.center() + vec2(0.0, screen.height() / 6.0),
+                    Align2::CENTER_CENTER,
+                    progress,
+                    FontId::proportional(20.0),
+                    visuals.strong_text_color(),
+                );
+            });
+    }
+}
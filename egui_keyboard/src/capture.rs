@@ -0,0 +1,297 @@
+//! Presses on the keyboard taken out of the egui input, so the focused widget keeps its focus.
+
+use crate::{hit_test, Keyboard};
+use egui::{Context, Event, PointerButton, Pos2, RawInput, Rect, Response, Ui};
+use std::collections::VecDeque;
+
+/// Press on the keyboard which egui does not know about, see [`Keyboard::filter_input`].
+#[derive(Clone, Copy)]
+pub(crate) struct CapturedPress {
+    origin: Pos2,
+    pos: Pos2,
+    /// When the press started, according to egui. Only known once the keyboard is shown.
+    start_time: Option<f64>,
+    released: bool,
+    /// Key the press was most likely meant for, if the fat finger compensation is enabled.
+    target: Option<Rect>,
+}
+
+impl CapturedPress {
+    fn new(pos: Pos2) -> Self {
+        Self {
+            origin: pos,
+            pos,
+            start_time: None,
+            released: false,
+            target: None,
+        }
+    }
+
+    fn hits(&self, rect: Rect) -> bool {
+        match self.target {
+            Some(target) => target == rect,
+            None => rect.contains(self.origin),
+        }
+    }
+}
+
+/// How the pointer interacts with a key, whether egui knows about the press or not.
+#[derive(Default)]
+pub(crate) struct KeyInteraction {
+    pub(crate) clicked: bool,
+    /// Whether the key was clicked with the secondary mouse button, e.g. the right one.
+    pub(crate) secondary_clicked: bool,
+    /// Whether the key is being pressed.
+    pub(crate) down: bool,
+    /// Where the press started.
+    pub(crate) origin: Option<Pos2>,
+    /// Where the pointer is, or where it was released.
+    pub(crate) pos: Option<Pos2>,
+    /// How long the key is being pressed, in seconds.
+    pub(crate) held: f64,
+    /// Whether egui does not know about the press, so it does not draw the key as pressed.
+    pub(crate) captured: bool,
+}
+
+impl Keyboard {
+    /// Take the presses on the keyboard out of the `raw_input`, before it is passed to egui.
+    ///
+    /// Egui takes the focus away from the focused widget whenever the pointer is pressed
+    /// elsewhere. The keyboard gives the focus back, but the input widget still loses it for
+    /// a frame with every key press, which confuses widgets reacting to `lost_focus`. Presses
+    /// taken out of the input never reach egui, so the focus stays where it is.
+    ///
+    /// The presses taken out are moved by the [`Keyboard::touch_offset`].
+    ///
+    /// With eframe, call it from `App::raw_input_hook`.
+    pub fn filter_input(&mut self, raw_input: &mut RawInput) {
+        if self.last_rects.is_empty() || self.visibility < 1. || self.is_calibrating_touch() {
+            self.clear_captured_presses();
+            return;
+        }
+
+        let mut presses = self.take_captured_presses();
+        raw_input
+            .events
+            .retain(|event| !self.track_press(&mut presses, event));
+        self.captured_press = presses.pop_front();
+        self.queued_presses = presses;
+    }
+
+    /// Take over the presses on the keyboard from egui when several of them landed in this
+    /// frame, e.g. at a low frame rate, as egui only reports the last one. They are handled one
+    /// per frame in the order they came, followed by the presses landing while they are.
+    pub(crate) fn capture_pressed_together(&mut self, ctx: &Context) {
+        if self.last_rects.is_empty() || self.disabled || self.is_calibrating_touch() {
+            return;
+        }
+        let events: Vec<Event> = ctx.input(|input| {
+            input
+                .events
+                .iter()
+                .filter(|event| {
+                    matches!(event, Event::PointerButton { .. } | Event::PointerMoved(_))
+                })
+                .cloned()
+                .collect()
+        });
+        let mut presses = self.take_captured_presses();
+        let pending = presses.len();
+        for event in &events {
+            self.track_press(&mut presses, event);
+        }
+        if pending > 0 || presses.len() > 1 {
+            self.captured_press = presses.pop_front();
+            self.queued_presses = presses;
+        }
+    }
+
+    /// Press at `pos` as if it was captured, after the presses in progress. The press is
+    /// released right away unless `held`, then it waits for [`Keyboard::release_queued_press`].
+    pub(crate) fn queue_press(&mut self, pos: Pos2, held: bool) {
+        let press = CapturedPress {
+            released: !held,
+            ..CapturedPress::new(pos)
+        };
+        match self.captured_press {
+            Some(_) => self.queued_presses.push_back(press),
+            None => self.captured_press = Some(press),
+        }
+    }
+
+    /// Release the last of the presses, if it is still held.
+    pub(crate) fn release_queued_press(&mut self) {
+        let last = match self.queued_presses.back_mut() {
+            Some(press) => Some(press),
+            None => self.captured_press.as_mut(),
+        };
+        if let Some(press) = last {
+            press.released = true;
+        }
+    }
+
+    /// The captured press followed by the queued ones.
+    fn take_captured_presses(&mut self) -> VecDeque<CapturedPress> {
+        let mut presses: VecDeque<_> = self.captured_press.take().into_iter().collect();
+        presses.append(&mut self.queued_presses);
+        presses
+    }
+
+    /// Follow the pointer `event` in the `presses`, returns whether it starts or ends one.
+    fn track_press(&self, presses: &mut VecDeque<CapturedPress>, event: &Event) -> bool {
+        let unreleased = presses.back_mut().filter(|press| !press.released);
+        let offset = self.touch_offset;
+        match *event {
+            Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed: true,
+                ..
+            } if self.covers(pos + offset) && !self.on_action_bar(pos + offset) => {
+                presses.push_back(CapturedPress::new(pos + offset));
+                true
+            }
+            Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed: false,
+                ..
+            } => match unreleased {
+                Some(press) => {
+                    press.pos = pos + offset;
+                    press.released = true;
+                    true
+                }
+                None => false,
+            },
+            Event::PointerMoved(pos) => {
+                if let Some(press) = unreleased {
+                    press.pos = pos + offset;
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Start timing the captured press once egui tells the time, and find out which key it was
+    /// meant for while the keys from the last frame are still known.
+    pub(crate) fn start_captured_press(&mut self, ctx: &Context) {
+        let Some(press) = &mut self.captured_press else {
+            return;
+        };
+        if press.start_time.is_none() {
+            press.start_time = Some(ctx.input(|input| input.time));
+            if self.fat_finger_compensation {
+                press.target = hit_test::most_likely_key(&self.key_rects, press.origin);
+            }
+        }
+    }
+
+    /// Forget the captured press once all the keys had a chance to react to its release, and
+    /// move on to the next queued one.
+    pub(crate) fn finish_captured_press(&mut self, ctx: &Context) {
+        if self.captured_press.is_some_and(|press| press.released) {
+            self.captured_press = self.queued_presses.pop_front();
+            if self.captured_press.is_some() {
+                ctx.request_repaint();
+            }
+        }
+    }
+
+    /// Forget the captured press and the queued ones.
+    pub(crate) fn clear_captured_presses(&mut self) {
+        self.captured_press = None;
+        self.queued_presses.clear();
+    }
+
+    /// Whether the primary pointer is down and where it is, whether egui knows about the press
+    /// or not.
+    pub(crate) fn primary_pointer(&self, ctx: &Context) -> (bool, Option<Pos2>) {
+        match self.captured_press {
+            Some(press) => (!press.released, Some(press.pos)),
+            None => ctx.input(|input| (input.pointer.primary_down(), input.pointer.latest_pos())),
+        }
+    }
+
+    /// Where the captured press started and where it was released, once it is released.
+    pub(crate) fn captured_tap(&self) -> Option<(Pos2, Pos2)> {
+        self.captured_press
+            .filter(|press| press.released)
+            .map(|press| (press.origin, press.pos))
+    }
+
+    /// How the pointer interacts with the key which got the `response`.
+    pub(crate) fn interaction(&self, ui: &Ui, response: &Response) -> KeyInteraction {
+        // Keys under the open alternates popup must not react to the taps choosing from it.
+        let popup_open = self
+            .alternates_popup
+            .as_ref()
+            .is_some_and(|popup| !popup.sliding());
+        if self.disabled || popup_open {
+            return KeyInteraction::default();
+        }
+        // Release of a repeating key is not a click, the key already did what it does.
+        let repeated = self
+            .repeating
+            .is_some_and(|repeating| repeating.key == response.id);
+
+        if let Some(press) = self
+            .captured_press
+            .filter(|press| press.hits(response.rect))
+        {
+            let time = ui.input(|input| input.time);
+            let max_click_dist = ui
+                .ctx()
+                .options(|options| options.input_options.max_click_dist);
+            // Compensated press might not be on the key at all, so only moving the pointer too
+            // far away cancels it. Otherwise it is a click only when the pointer stays put, the
+            // way egui decides it, so swiping over the key does not press it.
+            let released_on_key = match press.target {
+                Some(_) => press.pos.distance(press.origin) < response.rect.height() / 2.,
+                None => {
+                    response.rect.contains(press.pos)
+                        && press.pos.distance(press.origin) <= max_click_dist
+                }
+            };
+            return KeyInteraction {
+                clicked: press.released && released_on_key && !repeated,
+                secondary_clicked: false,
+                down: !press.released,
+                origin: Some(press.origin),
+                pos: Some(press.pos),
+                held: press.start_time.map_or(0., |start| time - start),
+                captured: true,
+            };
+        }
+        // Egui might still report the last of the presses taken over from it.
+        if self.captured_press.is_some() {
+            return KeyInteraction::default();
+        }
+
+        // Response reads the input as well, so it must not be asked from within `ui.input`.
+        let clicked = response.clicked() && !repeated;
+        let secondary_clicked = response.secondary_clicked();
+        let down = response.is_pointer_button_down_on();
+        let interact_pos = response.interact_pointer_pos();
+        ui.input(|input| KeyInteraction {
+            clicked,
+            secondary_clicked,
+            // Egui stops reporting the press once it is too long for a click, but the key is
+            // still held, e.g. to repeat.
+            down: down
+                || input.pointer.primary_down()
+                    && input
+                        .pointer
+                        .press_origin()
+                        .is_some_and(|origin| response.rect.contains(origin)),
+            origin: input.pointer.press_origin(),
+            pos: interact_pos.or(input.pointer.latest_pos()),
+            held: input
+                .pointer
+                .press_start_time()
+                .map_or(0., |start| input.time - start),
+            captured: false,
+        })
+    }
+}
@@ -0,0 +1,70 @@
+//! Entries of the suggestion bar drawn with the widgets of the application.
+
+use crate::{button, Keyboard};
+use egui::{Response, Ui, WidgetText};
+
+/// What an entry of the suggestion bar offers, see [`Keyboard::suggestion_renderer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SuggestionKind {
+    /// Result of the typed calculation, see [`Keyboard::calculator`].
+    Calculation,
+    /// Completion offered with [`Keyboard::set_completion`].
+    Completion,
+    /// Correction of the misspelled word, see [`Keyboard::set_misspelled`].
+    Correction,
+    /// Word from the [`Keyboard::suggestion_provider`].
+    Word,
+    /// Text offered with [`Keyboard::offer_suggestion`] or remembered by the
+    /// [`Keyboard::autofill_store`].
+    Offered,
+    /// Text from the clipboard.
+    Clipboard,
+}
+
+pub(crate) type SuggestionRenderer =
+    Box<dyn FnMut(&mut Ui, SuggestionKind, &str) -> Option<Response>>;
+
+impl Keyboard {
+    /// Draw the entries of the suggestion bar with own widgets instead of the plain buttons, e.g.
+    /// with the avatars of the contacts offered with [`Keyboard::offer_suggestion`]. The
+    /// `renderer` gets what the entry offers and its text, and returns the response of the
+    /// widget it added, which needs to sense clicks, or `None` for the plain button.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui::{Button, RichText};
+    /// use egui_keyboard::{Keyboard, SuggestionKind};
+    ///
+    /// let keyboard = Keyboard::default().suggestion_renderer(|ui, kind, text| {
+    ///     (kind == SuggestionKind::Offered && text.starts_with('#'))
+    ///         .then(|| ui.add(Button::new(RichText::new(text).strong())))
+    /// });
+    /// ```
+    pub fn suggestion_renderer(
+        mut self,
+        renderer: impl FnMut(&mut Ui, SuggestionKind, &str) -> Option<Response> + 'static,
+    ) -> Self {
+        self.suggestion_renderer = Some(Box::new(renderer));
+        self
+    }
+
+    /// Entry of the suggestion bar offering the `text`, drawn by the
+    /// [`Keyboard::suggestion_renderer`] or as a button with the `label`.
+    pub(crate) fn chip(
+        &mut self,
+        ui: &mut Ui,
+        kind: SuggestionKind,
+        text: &str,
+        label: impl Into<WidgetText>,
+    ) -> Response {
+        if let Some(response) = self
+            .suggestion_renderer
+            .as_mut()
+            .and_then(|renderer| renderer(ui, kind, text))
+        {
+            return response;
+        }
+        ui.add(button(label, self.style.min_key_size))
+    }
+}
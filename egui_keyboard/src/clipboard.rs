@@ -1,10 +1,202 @@
-#[cfg(target_os = "android")]
-pub(crate) fn get_text() -> Option<String> {
-    android_clipboard::get_text().ok()
+//! Text offered for pasting.
+
+/// Whether the keyboard may read the clipboard to offer its text for pasting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClipboardPermission {
+    /// Nobody asked yet. On the web, the browser asks the user when the clipboard is read for the
+    /// first time, which has to happen in response to a user gesture, like tapping the paste key.
+    #[default]
+    Unknown,
+    /// Waiting for the user or the browser to allow the access.
+    Pending,
+    Granted,
+    /// The access was denied, or the clipboard support is compiled out.
+    Denied,
+}
+
+/// How often the text offered for pasting is read from the clipboard while the keyboard is
+/// shown, see [`crate::Keyboard::clipboard_polling`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ClipboardPolling {
+    #[default]
+    EveryFrame,
+    /// At most once per this many seconds.
+    Interval(f64),
+    /// Once when the keyboard is shown.
+    OnShow,
+}
+
+/// Access to the system clipboard.
+#[derive(Default)]
+pub(crate) struct Clipboard {
+    #[cfg(all(feature = "clipboard", target_arch = "wasm32"))]
+    web: web::WebClipboard,
+    /// Text offered for pasting, as read at the time next to it.
+    offered: Option<String>,
+    read_at: Option<f64>,
+}
+
+impl Clipboard {
+    /// Text to offer for pasting, read again at the `time` if the `polling` allows it.
+    pub(crate) fn poll(&mut self, time: f64, polling: ClipboardPolling) -> Option<String> {
+        // On the web, the text comes from the reads in the background, so it is cheap to read.
+        let due = cfg!(all(feature = "clipboard", target_arch = "wasm32"))
+            || match (self.read_at, polling) {
+                (None, _) | (_, ClipboardPolling::EveryFrame) => true,
+                (Some(read_at), ClipboardPolling::Interval(seconds)) => time - read_at >= seconds,
+                (Some(_), ClipboardPolling::OnShow) => false,
+            };
+        if due {
+            self.offered = self.text();
+            self.read_at = Some(time);
+        }
+        self.offered.clone()
+    }
+
+    /// Read the clipboard again at the next poll, e.g. when the keyboard is being shown.
+    pub(crate) fn forget(&mut self) {
+        self.read_at = None;
+    }
+}
+
+#[cfg(all(feature = "clipboard", target_os = "android"))]
+impl Clipboard {
+    pub(crate) fn text(&self) -> Option<String> {
+        android_clipboard::get_text().ok()
+    }
+
+    pub(crate) fn permission(&self) -> ClipboardPermission {
+        ClipboardPermission::Granted
+    }
+
+    pub(crate) fn request(&mut self, _ctx: &egui::Context) {}
+
+    pub(crate) fn refresh(&mut self, _ctx: &egui::Context) {}
+}
+
+#[cfg(all(
+    feature = "clipboard",
+    not(any(target_os = "android", target_arch = "wasm32"))
+))]
+impl Clipboard {
+    pub(crate) fn text(&self) -> Option<String> {
+        let mut clipboard = arboard::Clipboard::new().ok()?;
+        clipboard.get_text().ok()
+    }
+
+    pub(crate) fn permission(&self) -> ClipboardPermission {
+        ClipboardPermission::Granted
+    }
+
+    pub(crate) fn request(&mut self, _ctx: &egui::Context) {}
+
+    pub(crate) fn refresh(&mut self, _ctx: &egui::Context) {}
 }
 
-#[cfg(not(target_os = "android"))]
-pub(crate) fn get_text() -> Option<String> {
-    let mut clipboard = arboard::Clipboard::new().ok()?;
-    clipboard.get_text().ok()
+/// Browsers only allow reading the clipboard asynchronously, and only after the user allows it.
+#[cfg(all(feature = "clipboard", target_arch = "wasm32"))]
+impl Clipboard {
+    /// Text read by the last request.
+    pub(crate) fn text(&self) -> Option<String> {
+        self.web.text()
+    }
+
+    pub(crate) fn permission(&self) -> ClipboardPermission {
+        self.web.permission()
+    }
+
+    /// Read the clipboard in the background. Needs to be called in response to a user gesture
+    /// unless the access was already granted.
+    pub(crate) fn request(&mut self, ctx: &egui::Context) {
+        self.web.read(ctx);
+    }
+
+    /// Read the clipboard again if it can be done without bothering the user.
+    pub(crate) fn refresh(&mut self, ctx: &egui::Context) {
+        if self.permission() == ClipboardPermission::Granted {
+            self.web.read(ctx);
+        }
+    }
+}
+
+#[cfg(not(feature = "clipboard"))]
+impl Clipboard {
+    /// Clipboard support is compiled out, so there is never anything to paste.
+    pub(crate) fn text(&self) -> Option<String> {
+        None
+    }
+
+    pub(crate) fn permission(&self) -> ClipboardPermission {
+        ClipboardPermission::Denied
+    }
+
+    pub(crate) fn request(&mut self, _ctx: &egui::Context) {}
+
+    pub(crate) fn refresh(&mut self, _ctx: &egui::Context) {}
+}
+
+#[cfg(all(feature = "clipboard", target_arch = "wasm32"))]
+mod web {
+    use super::ClipboardPermission;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Default)]
+    struct State {
+        permission: ClipboardPermission,
+        text: Option<String>,
+    }
+
+    /// Result of the last clipboard read, filled in by the browser in the background.
+    #[derive(Default)]
+    pub(super) struct WebClipboard {
+        state: Rc<RefCell<State>>,
+    }
+
+    impl WebClipboard {
+        pub(super) fn text(&self) -> Option<String> {
+            self.state.borrow().text.clone()
+        }
+
+        pub(super) fn permission(&self) -> ClipboardPermission {
+            self.state.borrow().permission
+        }
+
+        pub(super) fn read(&self, ctx: &egui::Context) {
+            let Some(window) = web_sys::window() else {
+                self.state.borrow_mut().permission = ClipboardPermission::Denied;
+                return;
+            };
+
+            {
+                let mut state = self.state.borrow_mut();
+                if state.permission == ClipboardPermission::Pending {
+                    return;
+                }
+                if state.permission == ClipboardPermission::Unknown {
+                    state.permission = ClipboardPermission::Pending;
+                }
+            }
+
+            let promise = window.navigator().clipboard().read_text();
+            let state = self.state.clone();
+            let ctx = ctx.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = wasm_bindgen_futures::JsFuture::from(promise).await;
+                let mut state = state.borrow_mut();
+                match result {
+                    Ok(text) => {
+                        state.permission = ClipboardPermission::Granted;
+                        state.text = text.as_string().filter(|text| !text.is_empty());
+                    }
+                    Err(_) => {
+                        state.permission = ClipboardPermission::Denied;
+                        state.text = None;
+                    }
+                }
+                ctx.request_repaint();
+            });
+        }
+    }
 }
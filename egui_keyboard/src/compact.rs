@@ -0,0 +1,90 @@
+//! Compact arrangement of the keys for keyboards too narrow for a key per letter, e.g. on a phone
+//! in split-screen.
+
+use crate::alternates::AlternatesPopup;
+use crate::layouts::Row;
+use crate::{Key, KeyCategory, Keyboard, Press};
+use egui::{Response, Ui, Vec2};
+
+/// Width of the screen below which the keys are grouped, in points, unless set with
+/// [`Keyboard::compact_width`].
+pub(crate) const COMPACT_WIDTH: f32 = 250.0;
+
+/// How many characters share a key of the compact arrangement.
+const GROUP_SIZE: usize = 3;
+
+/// Rows with at most this many characters in a row are narrow enough already, e.g. of a numpad.
+const MAX_UNGROUPED: usize = 4;
+
+impl Keyboard {
+    /// Group the characters of the rows by three per key when the screen is narrower than
+    /// `width` points, instead of squeezing a key per character into the keyboard. Placements
+    /// narrower than the screen, like the split halves, are grouped only on such screens too.
+    /// Tapping a group offers its characters in a popup, long-pressing it lets the finger
+    /// slide to one of them. Set it to `0.0` to never group the keys.
+    pub fn compact_width(mut self, width: f32) -> Self {
+        self.compact_width = Some(width.max(0.0));
+        self
+    }
+
+    /// Whether the keys are grouped on a screen `screen_width` points wide.
+    pub(crate) fn is_compact(&self, screen_width: f32) -> bool {
+        screen_width < self.compact_width.unwrap_or(COMPACT_WIDTH)
+    }
+
+    /// Key offering the `texts` in a popup, typing the one chosen there.
+    pub(crate) fn group_key(
+        &mut self,
+        ui: &mut Ui,
+        texts: &[String],
+        button_size: Option<Vec2>,
+    ) -> Response {
+        let label = texts.concat();
+        let response = self.add_key_button(ui, &label, false, KeyCategory::Character, button_size);
+        let popup = AlternatesPopup::new(response.id, response.rect, texts.to_vec());
+        match self.press(ui, &response) {
+            Press::None => {}
+            // The finger is lifted already, so the popup waits for the option to be tapped.
+            Press::Click => {
+                self.alternates_popup = Some(popup.lifted(ui.ctx().cumulative_pass_nr()))
+            }
+            Press::Long => self.alternates_popup = Some(popup),
+        }
+        self.slide_over_alternates(ui.ctx(), response.id);
+        response
+    }
+}
+
+/// The `rows` with the runs of more than [`MAX_UNGROUPED`] characters grouped by
+/// [`GROUP_SIZE`] per key. The other keys stay as they are.
+pub(crate) fn group_keys(rows: Vec<Row>) -> Vec<Row> {
+    rows.into_iter()
+        .map(|row| {
+            let mut keys = Vec::new();
+            let mut run = Vec::new();
+            for key in row.keys.into_iter().chain([Key::Gap(0.0)]) {
+                if let Key::Text(text) = key {
+                    run.push(text);
+                    continue;
+                }
+                if run.len() > MAX_UNGROUPED {
+                    keys.extend(run.chunks(GROUP_SIZE).map(|texts| match texts {
+                        [text] => Key::Text(text.clone()),
+                        texts => Key::Group(texts.to_vec()),
+                    }));
+                } else {
+                    keys.extend(run.drain(..).map(Key::Text));
+                }
+                run.clear();
+                keys.push(key);
+            }
+            // Drop the gap ending the last run.
+            keys.pop();
+            Row {
+                keys,
+                offset: 0.0,
+                ..row
+            }
+        })
+        .collect()
+}
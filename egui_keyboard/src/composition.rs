@@ -0,0 +1,58 @@
+//! Text which is being composed, e.g. after a dead key, shown before it is committed.
+
+use crate::Keyboard;
+use egui::{vec2, Align2, Area, Context, Frame, Id, Order, RichText};
+
+/// Space between the hint and the caret or the keyboard.
+const HINT_GAP: f32 = 4.0;
+
+impl Keyboard {
+    /// Show the `text` which is being composed for the widget with given `id`, e.g. after a dead
+    /// key, in the middle of a Hangul syllable or in a pinyin buffer, so the user sees what will
+    /// be committed. The keyboard shows it in a small hint above the caret of the widget, or
+    /// above the keyboard if the caret is not known.
+    ///
+    /// The keyboard does not compose the text itself, it is up to the input method of the
+    /// application, e.g. a [`crate::KeyMode::Custom`] keeping the pending keys. Like the
+    /// completion, the composition is only shown for the current frame, so this needs to be
+    /// called every frame before [`Keyboard::show`].
+    pub fn set_composition(&mut self, id: Id, text: impl Into<String>) {
+        self.composition = Some((id, text.into()));
+    }
+
+    /// Show the hint with the composition offered for the focused widget.
+    pub(crate) fn show_composition(&self, ctx: &Context) {
+        let Some(text) = self
+            .composition
+            .as_ref()
+            .filter(|(id, text)| Some(*id) == self.input_widget && !text.is_empty())
+            .map(|(_, text)| text)
+        else {
+            return;
+        };
+
+        // Focused text edit tells where its caret is, for the native IME.
+        let caret = ctx.output(|output| output.ime.map(|ime| ime.cursor_rect));
+        let (pivot, pos) = match (caret, self.last_rect()) {
+            // Caret at the top of the screen leaves no room for the hint above it.
+            (Some(caret), _) if caret.top() - ctx.screen_rect().top() < 2. * caret.height() => (
+                Align2::LEFT_TOP,
+                caret.left_bottom() + vec2(0., 2. * HINT_GAP),
+            ),
+            (Some(caret), _) => (Align2::LEFT_BOTTOM, caret.left_top()),
+            (None, Some(keyboard)) => (Align2::CENTER_BOTTOM, keyboard.center_top()),
+            (None, None) => return,
+        };
+
+        Area::new(Id::new("keyboard_composition"))
+            .order(Order::Tooltip)
+            .interactable(false)
+            .pivot(pivot)
+            .fixed_pos(pos - vec2(0., HINT_GAP))
+            .show(ctx, |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(RichText::new(text).heading().underline());
+                });
+            });
+    }
+}
@@ -0,0 +1,287 @@
+//! All settings of the keyboard in one place.
+
+use crate::compact::COMPACT_WIDTH;
+use crate::confirm::CONFIRM_DURATION;
+use crate::layouts::{KeyboardLayout, LayoutKey};
+use crate::physical::MIN_KEY_SIZE_MM;
+use crate::{
+    debug, BottomRowConfig, ClipboardPolling, Digits, FocusChangePolicy, Handedness, Keyboard,
+    KeyboardPlacement, KeyboardStyle, LayerTransition, OverflowPolicy, SafeAreaInsets,
+    ScrollBehavior, TextBatching, DEFAULT_EVENT_CAPACITY, LONG_PRESS_DURATION, REPEAT_DELAY,
+    REPEAT_INTERVAL,
+};
+use egui::{Color32, Vec2};
+
+/// All settings of the keyboard, e.g. for a settings screen of the application. Apply them with
+/// [`Keyboard::apply_config`] and read the current ones with [`Keyboard::config`].
+///
+/// With the `serde` feature, the settings can be stored and loaded, see
+/// [`KeyboardConfig::from_file`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct KeyboardConfig {
+    pub layout: KeyboardLayout,
+    pub style: KeyboardStyle,
+
+    /// Labels of the shift key when the upper case is active and when it is not, `None` keeps
+    /// the labels the keyboard has, e.g. from [`Keyboard::new`].
+    pub shift_characters: Option<[char; 2]>,
+    /// Label of the backspace key, `None` keeps the label the keyboard has.
+    pub backspace_character: Option<char>,
+
+    pub placement: KeyboardPlacement,
+    /// See [`Keyboard::background_opacity`].
+    pub background_opacity: f32,
+    /// See [`Keyboard::scrim`].
+    pub scrim: Option<Color32>,
+    pub safe_area_insets: SafeAreaInsets,
+    /// See [`Keyboard::accessibility_scale`].
+    pub accessibility_scale: f32,
+    /// See [`Keyboard::display_dpi`].
+    pub display_dpi: Option<f32>,
+    /// See [`Keyboard::min_key_size_mm`].
+    pub min_key_size_mm: f32,
+    /// Narrowest keys before the layout scrolls, see [`Keyboard::scroll_wide_layouts`].
+    pub scroll_min_key_width: Option<f32>,
+    /// See [`Keyboard::compact_width`].
+    pub compact_width: f32,
+    pub scroll_behavior: ScrollBehavior,
+    /// See [`Keyboard::show_animation`].
+    pub show_animation: Option<f32>,
+    /// See [`Keyboard::settle_frames`].
+    pub settle_frames: u32,
+    /// See [`Keyboard::layer_transition`].
+    pub layer_transition: LayerTransition,
+    /// See [`Keyboard::selection_row`].
+    pub selection_row: bool,
+    pub handedness: Handedness,
+    /// See [`Keyboard::fat_finger_compensation`].
+    pub fat_finger_compensation: bool,
+    /// See [`Keyboard::flick_gestures`].
+    pub flick_gestures: bool,
+    /// See [`Keyboard::language_on_space`].
+    pub language_on_space: bool,
+    /// See [`Keyboard::blocked_words`].
+    pub blocked_words: Vec<String>,
+    /// See [`Keyboard::calculator`].
+    pub calculator: bool,
+    /// See [`Keyboard::numpad_companion`].
+    pub numpad_companion: bool,
+    /// See [`Keyboard::digits`].
+    pub digits: Digits,
+    /// Keys hidden in all the layouts, see [`Keyboard::set_key_visible`].
+    pub hidden_keys: Vec<LayoutKey>,
+    /// See [`Keyboard::bottom_row`].
+    pub bottom_row: BottomRowConfig,
+    /// See [`Keyboard::clipboard`].
+    pub clipboard: bool,
+    /// See [`Keyboard::clipboard_polling`].
+    pub clipboard_polling: ClipboardPolling,
+    /// See [`Keyboard::gestures`].
+    pub gestures: bool,
+    /// See [`Keyboard::long_press_duration`].
+    pub long_press_duration: f64,
+    /// See [`Keyboard::confirm_duration`].
+    pub confirm_duration: f64,
+    /// See [`Keyboard::touch_offset`].
+    pub touch_offset: Vec2,
+    /// Delay and interval of repeating keys, see [`Keyboard::key_repeat`].
+    pub key_repeat: (f64, f64),
+
+    /// See [`Keyboard::event_capacity`].
+    pub event_capacity: usize,
+    /// See [`Keyboard::overflow_policy`].
+    pub overflow_policy: OverflowPolicy,
+    /// See [`Keyboard::focus_change_policy`].
+    pub focus_change_policy: FocusChangePolicy,
+    /// See [`Keyboard::text_batching`].
+    pub text_batching: TextBatching,
+
+    /// See [`Keyboard::debug_heatmap`].
+    pub debug_heatmap: bool,
+    /// See [`Keyboard::debug_metrics`].
+    pub debug_metrics: bool,
+}
+
+impl Default for KeyboardConfig {
+    /// Settings of the [`Keyboard::default`], keeping the labels of the keyboard they are
+    /// applied to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui_keyboard::{Keyboard, KeyboardConfig};
+    ///
+    /// let mut keyboard = Keyboard::new(['⇧', '⇪'], '⌫');
+    /// keyboard.apply_config(KeyboardConfig::default());
+    /// assert_eq!(keyboard.config().shift_characters, Some(['⇧', '⇪']));
+    /// assert_eq!(keyboard.config().backspace_character, Some('⌫'));
+    /// ```
+    fn default() -> Self {
+        Self {
+            shift_characters: None,
+            backspace_character: None,
+            ..Keyboard::default().config()
+        }
+    }
+}
+
+impl KeyboardConfig {
+    /// Settings for a kiosk, where the keyboard only types: the emoji and the globe keys are
+    /// hidden, the clipboard is turned off, the keys have [`KeyboardStyle::high_contrast`] colors
+    /// and no gestures, and the keyboard is docked, so it cannot be moved or resized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut keyboard = egui_keyboard::Keyboard::new(['⬆', '⬆'], '◀');
+    /// keyboard.apply_config(egui_keyboard::KeyboardConfig::kiosk());
+    /// assert!(!keyboard.config().gestures);
+    /// assert_eq!(keyboard.config().backspace_character, Some('◀'));
+    /// ```
+    pub fn kiosk() -> Self {
+        Self {
+            style: KeyboardStyle::high_contrast(),
+            placement: KeyboardPlacement::Docked,
+            flick_gestures: false,
+            hidden_keys: vec![LayoutKey::Emoji, LayoutKey::Globe],
+            clipboard: false,
+            gestures: false,
+            ..Self::default()
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl KeyboardConfig {
+    /// Load the settings from a RON file. Settings which are left out keep their defaults.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, crate::LoadError> {
+        crate::load::from_file(path)
+    }
+
+    /// Parse the settings from a RON string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let config = egui_keyboard::KeyboardConfig::from_ron(
+    ///     "(layout: Colemak, selection_row: true, style: (key_corner_radius: 8.0))",
+    /// )
+    /// .unwrap();
+    /// assert!(config.selection_row);
+    /// ```
+    pub fn from_ron(ron: &str) -> Result<Self, crate::LoadError> {
+        crate::load::from_ron(ron)
+    }
+}
+
+impl Keyboard {
+    /// Apply all the settings at once. The state of the keyboard, like the active layer or the
+    /// debug heatmap collected so far, is kept where possible.
+    pub fn apply_config(&mut self, config: KeyboardConfig) {
+        self.keyboard_layout = config.layout;
+        self.style = config.style;
+        if let Some(shift_characters) = config.shift_characters {
+            self.shift_characters = shift_characters;
+        }
+        if let Some(backspace_character) = config.backspace_character {
+            self.backspace_character = backspace_character;
+        }
+        self.background_opacity = Some(config.background_opacity.clamp(0.0, 1.0));
+        self.placement = config.placement;
+        self.scrim = config.scrim;
+        self.safe_area_insets = config.safe_area_insets;
+        self.accessibility_scale = Some(config.accessibility_scale.max(1.0));
+        self.display_dpi = config.display_dpi.filter(|dpi| *dpi > 0.0);
+        self.min_key_size_mm = Some(config.min_key_size_mm.max(0.0));
+        self.scroll_min_key_width = config.scroll_min_key_width.map(|width| width.max(1.0));
+        self.compact_width = Some(config.compact_width.max(0.0));
+        self.scroll_behavior = config.scroll_behavior;
+        self.show_animation = config.show_animation.map(|time| time.max(0.0));
+        self.settle_frames = config.settle_frames;
+        self.layer_transition = config.layer_transition;
+        self.selection_row = config.selection_row;
+        self.handedness = config.handedness;
+        self.fat_finger_compensation = config.fat_finger_compensation;
+        self.flick_gestures = config.flick_gestures;
+        self.language_on_space = config.language_on_space;
+        self.blocked_words.clear();
+        self.block_words(config.blocked_words);
+        self.calculator = config.calculator;
+        self.numpad_companion = config.numpad_companion;
+        self.digits = config.digits;
+        self.hidden_keys = config.hidden_keys;
+        self.bottom_row = config.bottom_row;
+        self.clipboard_disabled = !config.clipboard;
+        self.clipboard_polling = config.clipboard_polling;
+        self.gestures_disabled = !config.gestures;
+        self.long_press_duration = Some(config.long_press_duration.max(0.0));
+        self.confirm_duration = Some(config.confirm_duration.max(0.0));
+        self.touch_offset = config.touch_offset;
+        let (delay, interval) = config.key_repeat;
+        self.key_repeat = Some((delay.max(0.0), interval.max(0.01)));
+        self.event_capacity = Some(config.event_capacity);
+        self.overflow_policy = config.overflow_policy;
+        self.focus_change_policy = config.focus_change_policy;
+        self.text_batching = config.text_batching;
+        if !config.debug_heatmap {
+            self.heatmap = None;
+        } else if self.heatmap.is_none() {
+            self.heatmap = Some(debug::Heatmap::default());
+        }
+        self.debug_metrics = config.debug_metrics;
+    }
+
+    /// Current settings of the keyboard.
+    pub fn config(&self) -> KeyboardConfig {
+        let mut blocked_words: Vec<String> = self.blocked_words.iter().cloned().collect();
+        blocked_words.sort();
+        KeyboardConfig {
+            layout: self.keyboard_layout.clone(),
+            style: self.style.clone(),
+            shift_characters: Some(self.shift_characters),
+            backspace_character: Some(self.backspace_character),
+            background_opacity: self.background_opacity.unwrap_or(1.0),
+            placement: self.placement,
+            scrim: self.scrim,
+            safe_area_insets: self.safe_area_insets,
+            accessibility_scale: self.accessibility_scale.unwrap_or(1.0),
+            display_dpi: self.display_dpi,
+            min_key_size_mm: self.min_key_size_mm.unwrap_or(MIN_KEY_SIZE_MM),
+            scroll_min_key_width: self.scroll_min_key_width,
+            compact_width: self.compact_width.unwrap_or(COMPACT_WIDTH),
+            scroll_behavior: self.scroll_behavior,
+            show_animation: self.show_animation,
+            settle_frames: self.settle_frames,
+            layer_transition: self.layer_transition,
+            selection_row: self.selection_row,
+            handedness: self.handedness,
+            fat_finger_compensation: self.fat_finger_compensation,
+            flick_gestures: self.flick_gestures,
+            language_on_space: self.language_on_space,
+            blocked_words,
+            calculator: self.calculator,
+            numpad_companion: self.numpad_companion,
+            digits: self.digits,
+            hidden_keys: self.hidden_keys.clone(),
+            bottom_row: self.bottom_row,
+            clipboard: !self.clipboard_disabled,
+            clipboard_polling: self.clipboard_polling,
+            gestures: !self.gestures_disabled,
+            long_press_duration: self.long_press_duration.unwrap_or(LONG_PRESS_DURATION),
+            confirm_duration: self.confirm_duration.unwrap_or(CONFIRM_DURATION),
+            touch_offset: self.touch_offset,
+            key_repeat: self.key_repeat.unwrap_or((REPEAT_DELAY, REPEAT_INTERVAL)),
+            event_capacity: self.event_capacity.unwrap_or(DEFAULT_EVENT_CAPACITY),
+            overflow_policy: self.overflow_policy,
+            focus_change_policy: self.focus_change_policy,
+            text_batching: self.text_batching,
+            debug_heatmap: self.heatmap.is_some(),
+            debug_metrics: self.debug_metrics,
+        }
+    }
+}
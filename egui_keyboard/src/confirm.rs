@@ -0,0 +1,82 @@
+//! Keys which need to be held to confirm what they do, see [`LayoutKey::HoldToConfirm`].
+//!
+//! [`LayoutKey::HoldToConfirm`]: crate::layouts::LayoutKey::HoldToConfirm
+
+use crate::{KeyAction, KeyCategory, Keyboard};
+use egui::{vec2, Pos2, Rect, Response, Shape, Stroke, Ui, Vec2};
+use std::f32::consts::TAU;
+
+/// How long the keys confirming what they do have to be held, in seconds, unless set with
+/// [`Keyboard::confirm_duration`].
+pub(crate) const CONFIRM_DURATION: f64 = 0.8;
+
+/// Radius of the ring showing the progress, relative to the smaller side of the key.
+const RING_RADIUS_FRACTION: f32 = 0.4;
+
+const RING_WIDTH: f32 = 3.0;
+
+/// How many segments the full ring is drawn with.
+const RING_SEGMENTS: usize = 48;
+
+impl Keyboard {
+    /// How long the [`crate::layouts::LayoutKey::HoldToConfirm`] keys have to be held, in
+    /// seconds, before they do what they do.
+    pub fn confirm_duration(mut self, seconds: f64) -> Self {
+        self.confirm_duration = Some(seconds.max(0.0));
+        self
+    }
+
+    /// Key doing the `actions` once it is held long enough, with a ring filling up meanwhile.
+    pub(crate) fn confirm_key(
+        &mut self,
+        ui: &mut Ui,
+        label: &str,
+        actions: Vec<KeyAction>,
+        category: KeyCategory,
+        button_size: Option<Vec2>,
+    ) -> Response {
+        let response = self.add_key_button(ui, label, false, category, button_size);
+        let interaction = self.interaction(ui, &response);
+        let confirmed = self.confirmed == Some(response.id);
+        if !interaction.down {
+            if confirmed {
+                self.confirmed = None;
+            }
+            return response;
+        }
+        // Sliding off the key cancels it.
+        let on_key = interaction
+            .pos
+            .is_some_and(|pos| response.rect.contains(pos));
+        if confirmed || !on_key {
+            return response;
+        }
+
+        let duration = self.confirm_duration.unwrap_or(CONFIRM_DURATION);
+        if interaction.held >= duration {
+            self.confirmed = Some(response.id);
+            self.perform(actions);
+            self.focus_back_to_input_widget(ui.ctx());
+        } else {
+            let progress = (interaction.held / duration.max(f64::EPSILON)) as f32;
+            let color = ui.visuals().selection.stroke.color;
+            ui.painter()
+                .add(progress_ring(response.rect, progress, color));
+            ui.ctx().request_repaint();
+        }
+        response
+    }
+}
+
+/// Ring in the middle of the key `rect`, filled clockwise from the top up to the `progress`.
+fn progress_ring(rect: Rect, progress: f32, color: egui::Color32) -> Shape {
+    let radius = rect.width().min(rect.height()) * RING_RADIUS_FRACTION;
+    let segments = ((RING_SEGMENTS as f32 * progress).ceil() as usize).max(1);
+    let points: Vec<Pos2> = (0..=segments)
+        .map(|i| {
+            let angle = TAU * progress * i as f32 / segments as f32 - TAU / 4.0;
+            rect.center() + radius * vec2(angle.cos(), angle.sin())
+        })
+        .collect();
+    Shape::line(points, Stroke::new(RING_WIDTH, color))
+}
@@ -0,0 +1,94 @@
+//! Developer overlays which help with tuning layouts on real hardware.
+
+use egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Stroke, StrokeKind, Vec2};
+use std::collections::HashMap;
+
+/// How many presses are remembered for every key. Older ones are forgotten.
+const MAX_PRESSES_PER_KEY: usize = 64;
+
+/// Where the presses landed relative to the centers of the keys they hit.
+///
+/// Offsets are normalized to the key size, so `(-1, -1)` is the top left corner of the key,
+/// `(0, 0)` its center and `(1, 1)` the bottom right corner.
+#[derive(Default)]
+pub(crate) struct Heatmap {
+    presses: HashMap<String, Vec<Vec2>>,
+}
+
+impl Heatmap {
+    pub(crate) fn record(&mut self, key: &str, rect: Rect, pos: Pos2) {
+        let half_size = rect.size() / 2.0;
+        if half_size.x <= 0.0 || half_size.y <= 0.0 {
+            return;
+        }
+
+        let offset = (pos - rect.center()) / half_size;
+        let presses = self.presses.entry(key.to_owned()).or_default();
+        if presses.len() >= MAX_PRESSES_PER_KEY {
+            presses.remove(0);
+        }
+        presses.push(offset);
+    }
+
+    /// Draw the presses recorded for `key` over its `rect`.
+    pub(crate) fn paint(&self, painter: &Painter, key: &str, rect: Rect) {
+        let Some(presses) = self.presses.get(key) else {
+            return;
+        };
+
+        let half_size = rect.size() / 2.0;
+        let radius = rect.size().min_elem() * 0.1;
+        for offset in presses {
+            painter.circle_filled(
+                rect.center() + *offset * half_size,
+                radius,
+                Color32::from_rgba_unmultiplied(255, 0, 0, 40),
+            );
+        }
+
+        if let Some(mean) = mean(presses.iter()) {
+            let center = rect.center() + mean * half_size;
+            let stroke = Stroke::new(radius / 4.0, Color32::YELLOW);
+            painter.line_segment(
+                [center - Vec2::X * radius, center + Vec2::X * radius],
+                stroke,
+            );
+            painter.line_segment(
+                [center - Vec2::Y * radius, center + Vec2::Y * radius],
+                stroke,
+            );
+        }
+    }
+
+    /// Average offset of all recorded presses, regardless of the key.
+    pub(crate) fn mean_offset(&self) -> Option<Vec2> {
+        mean(self.presses.values().flatten())
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.presses.clear();
+    }
+}
+
+fn mean<'a>(offsets: impl Iterator<Item = &'a Vec2>) -> Option<Vec2> {
+    let (sum, count) = offsets.fold((Vec2::ZERO, 0), |(sum, count), offset| {
+        (sum + *offset, count + 1)
+    });
+    (count > 0).then(|| sum / count as f32)
+}
+
+/// Outline `rect` and print `description` over it, so the computed sizes can be compared with what
+/// was actually rendered.
+pub(crate) fn paint_metrics(painter: &Painter, rect: Rect, description: &str) {
+    let color = Color32::from_rgb(255, 0, 255);
+    painter.rect_stroke(rect, 0.0, Stroke::new(1.0_f32, color), StrokeKind::Inside);
+
+    let galley = painter.layout_no_wrap(
+        description.to_owned(),
+        FontId::monospace(10.0),
+        Color32::WHITE,
+    );
+    let text_rect = Align2::RIGHT_TOP.anchor_size(rect.right_top(), galley.size());
+    painter.rect_filled(text_rect, 0.0, Color32::from_black_alpha(180));
+    painter.galley(text_rect.min, galley, Color32::WHITE);
+}
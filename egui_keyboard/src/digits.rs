@@ -0,0 +1,63 @@
+//! Digits of other scripts than Latin.
+
+use std::borrow::Cow;
+
+/// Digits typed by the number keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Digits {
+    /// 0123456789
+    #[default]
+    Ascii,
+    /// ٠١٢٣٤٥٦٧٨٩, used with Arabic.
+    ArabicIndic,
+    /// ۰۱۲۳۴۵۶۷۸۹, used with Persian and Urdu.
+    ExtendedArabicIndic,
+    /// ०१२३४५६७८९
+    Devanagari,
+    /// ০১২৩৪৫৬৭৮৯
+    Bengali,
+}
+
+impl Digits {
+    /// Code point of the zero digit.
+    fn zero(self) -> char {
+        match self {
+            Self::Ascii => '0',
+            Self::ArabicIndic => '\u{0660}',
+            Self::ExtendedArabicIndic => '\u{06F0}',
+            Self::Devanagari => '\u{0966}',
+            Self::Bengali => '\u{09E6}',
+        }
+    }
+
+    /// Replace the ASCII digits of the `text` with these digits.
+    pub(crate) fn localize(self, text: &str) -> Cow<'_, str> {
+        if self == Self::Ascii || !text.chars().any(|c| c.is_ascii_digit()) {
+            return Cow::Borrowed(text);
+        }
+
+        let zero = self.zero() as u32;
+        text.chars()
+            .map(|c| match c.to_digit(10) {
+                Some(digit) if c.is_ascii_digit() => char::from_u32(zero + digit).unwrap_or(c),
+                _ => c,
+            })
+            .collect()
+    }
+
+    /// Replace these digits in the `text` with the ASCII ones.
+    pub(crate) fn delocalize(self, text: &str) -> Cow<'_, str> {
+        if self == Self::Ascii {
+            return Cow::Borrowed(text);
+        }
+
+        let zero = self.zero() as u32;
+        text.chars()
+            .map(|c| match (c as u32).checked_sub(zero) {
+                Some(digit) if digit < 10 => char::from_digit(digit, 10).unwrap_or(c),
+                _ => c,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,93 @@
+//! Keys hidden or disabled by the application, e.g. the emoji key in a kiosk.
+
+use crate::layouts::{LayoutKey, Row};
+use crate::{Key, KeyAction, Keyboard};
+use egui::{Response, Ui, Vec2};
+
+/// Whether the `key` of a layout is the one the application refers to with the `layout_key`.
+/// Keys pressing a key, like Enter, match regardless of their label.
+fn matches(layout_key: &LayoutKey, key: &Key) -> bool {
+    match (layout_key, key.base()) {
+        (
+            LayoutKey::Tall { key: inner, .. }
+            | LayoutKey::Repeat(inner)
+            | LayoutKey::HoldToConfirm(inner)
+            | LayoutKey::WithAlternates { key: inner, .. }
+            | LayoutKey::Alternate { key: inner, .. },
+            _,
+        ) => matches(inner, key),
+        (LayoutKey::Text(a), Key::Text(b)) => a == b,
+        (
+            LayoutKey::Labeled { label, text },
+            Key::Labeled {
+                label: other_label,
+                text: other_text,
+            },
+        ) => label == other_label && text == other_text,
+        (LayoutKey::Key { key, modifiers, .. }, Key::Action { actions, .. }) => matches!(
+            actions.as_slice(),
+            [KeyAction::Key { key: other_key, modifiers: other_modifiers }]
+                if other_key == key && other_modifiers == modifiers
+        ),
+        (LayoutKey::Space, Key::Space)
+        | (LayoutKey::Backspace, Key::Backspace)
+        | (LayoutKey::Shift, Key::Upper)
+        | (LayoutKey::Special, Key::Special)
+        | (LayoutKey::Emoji, Key::EmojiLayer)
+        | (LayoutKey::Globe, Key::Globe) => true,
+        _ => false,
+    }
+}
+
+impl Keyboard {
+    /// Show or hide the `key` in all the layouts, e.g. hide [`LayoutKey::Emoji`] in a kiosk.
+    /// The rest of its row takes the space it leaves.
+    pub fn set_key_visible(&mut self, key: LayoutKey, visible: bool) {
+        self.hidden_keys.retain(|hidden| *hidden != key);
+        if !visible {
+            self.hidden_keys.push(key);
+        }
+    }
+
+    /// Enable or disable the `key` in all the layouts, e.g. disable [`LayoutKey::Space`] while
+    /// a user name is typed. Disabled keys are dimmed and do nothing when pressed.
+    pub fn set_key_enabled(&mut self, key: LayoutKey, enabled: bool) {
+        self.disabled_keys.retain(|disabled| *disabled != key);
+        if !enabled {
+            self.disabled_keys.push(key);
+        }
+    }
+
+    /// Take the hidden keys out of the `rows`, along with the rows left empty.
+    pub(crate) fn remove_hidden_keys(&self, rows: &mut Vec<Row>) {
+        if self.hidden_keys.is_empty() {
+            return;
+        }
+        for row in rows.iter_mut() {
+            row.keys
+                .retain(|key| !self.hidden_keys.iter().any(|hidden| matches(hidden, key)));
+        }
+        rows.retain(|row| !row.keys.is_empty());
+    }
+
+    pub(crate) fn is_key_disabled(&self, key: &Key) -> bool {
+        self.disabled_keys
+            .iter()
+            .any(|disabled| matches(disabled, key))
+    }
+
+    /// Dimmed key which does nothing when pressed.
+    pub(crate) fn disabled_key(
+        &mut self,
+        ui: &mut Ui,
+        key: &Key,
+        pages_count: usize,
+        button_size: Option<Vec2>,
+    ) -> Response {
+        let label = self.key_label(key, pages_count);
+        ui.add_enabled_ui(false, |ui| {
+            self.add_key_button(ui, &label, false, key.category(), button_size)
+        })
+        .inner
+    }
+}
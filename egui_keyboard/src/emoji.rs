@@ -0,0 +1,213 @@
+//! Emojis offered by the emoji layer.
+
+/// Emoji with the data used to find it.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct Emoji {
+    /// Text typed by the emoji key.
+    pub emoji: String,
+    pub name: String,
+    /// Emojis of the same category are shown together.
+    pub category: String,
+    pub keywords: Vec<String>,
+}
+
+/// Emojis offered by the emoji layer, in the order they are shown.
+///
+/// With the `emoji` feature, which is enabled by default, the default dataset holds a selection
+/// of common emojis. Without it, the default dataset is empty and the emoji layer is not offered
+/// unless a dataset is given with [`crate::Keyboard::emoji_dataset`]. With the `serde` feature,
+/// the dataset can be loaded from a RON file, e.g. to localize the names.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct EmojiDataset {
+    pub emojis: Vec<Emoji>,
+}
+
+/// How many emojis are shown at once. Categories with more emojis are split into several tabs.
+pub(crate) const EMOJIS_PER_TAB: usize = 30;
+
+/// How many emojis are shown in a row.
+pub(crate) const EMOJI_COLUMNS: usize = 10;
+
+impl Default for EmojiDataset {
+    fn default() -> Self {
+        #[cfg(feature = "emoji")]
+        return Self::builtin();
+        #[cfg(not(feature = "emoji"))]
+        return Self { emojis: Vec::new() };
+    }
+}
+
+impl EmojiDataset {
+    /// Common emojis with English names.
+    #[cfg(feature = "emoji")]
+    pub fn builtin() -> Self {
+        let emojis = BUILTIN
+            .iter()
+            .flat_map(|(category, emojis)| {
+                emojis.iter().map(|(emoji, name, keywords)| Emoji {
+                    emoji: emoji.to_string(),
+                    name: name.to_string(),
+                    category: category.to_string(),
+                    keywords: keywords.iter().map(|keyword| keyword.to_string()).collect(),
+                })
+            })
+            .collect();
+        Self { emojis }
+    }
+
+    /// Emojis whose name or keywords contain the `query`, ignoring the case.
+    pub fn search(&self, query: &str) -> Vec<&Emoji> {
+        let query = query.to_lowercase();
+        self.emojis
+            .iter()
+            .filter(|emoji| {
+                emoji.name.to_lowercase().contains(&query)
+                    || emoji
+                        .keywords
+                        .iter()
+                        .any(|keyword| keyword.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// Tabs of the emoji layer, as ranges of the emojis they show. Every category gets at least
+    /// one tab, the dataset is expected to keep the emojis of a category together.
+    pub(crate) fn tabs(&self) -> Vec<std::ops::Range<usize>> {
+        let mut tabs: Vec<std::ops::Range<usize>> = Vec::new();
+        for (i, emoji) in self.emojis.iter().enumerate() {
+            match tabs.last_mut() {
+                Some(tab)
+                    if tab.len() < EMOJIS_PER_TAB
+                        && self.emojis[tab.start].category == emoji.category =>
+                {
+                    tab.end = i + 1;
+                }
+                _ => tabs.push(i..i + 1),
+            }
+        }
+        tabs
+    }
+}
+
+#[cfg(feature = "serde")]
+impl EmojiDataset {
+    /// Load the dataset from a RON file.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, crate::LoadError> {
+        crate::load::from_file(path)
+    }
+
+    /// Parse the dataset from a RON string.
+    pub fn from_ron(ron: &str) -> Result<Self, crate::LoadError> {
+        crate::load::from_ron(ron)
+    }
+}
+
+/// Emoji, its name and keywords.
+#[cfg(feature = "emoji")]
+type BuiltinEmoji = (&'static str, &'static str, &'static [&'static str]);
+
+#[cfg(feature = "emoji")]
+const BUILTIN: &[(&str, &[BuiltinEmoji])] = &[
+    (
+        "Smileys",
+        &[
+            ("😀", "grinning face", &["smile", "happy"]),
+            ("😃", "grinning face with big eyes", &["smile", "happy"]),
+            ("😄", "grinning face with smiling eyes", &["smile", "happy"]),
+            ("😁", "beaming face", &["grin"]),
+            ("😆", "grinning squinting face", &["laugh"]),
+            ("😅", "grinning face with sweat", &["relief"]),
+            ("😂", "face with tears of joy", &["laugh", "lol"]),
+            ("🙂", "slightly smiling face", &["smile"]),
+            ("😉", "winking face", &["wink"]),
+            ("😊", "smiling face with smiling eyes", &["blush"]),
+            ("😇", "smiling face with halo", &["angel"]),
+            ("😍", "smiling face with heart-eyes", &["love"]),
+            ("😘", "face blowing a kiss", &["kiss", "love"]),
+            ("😋", "face savoring food", &["yum", "delicious"]),
+            ("😛", "face with tongue", &["tongue"]),
+            ("😎", "smiling face with sunglasses", &["cool"]),
+            ("🤔", "thinking face", &["hmm", "think"]),
+            ("😐", "neutral face", &["meh"]),
+            ("😴", "sleeping face", &["sleep", "tired"]),
+            ("😮", "face with open mouth", &["wow", "surprised"]),
+            ("😢", "crying face", &["sad", "tear"]),
+            ("😭", "loudly crying face", &["sad", "sob"]),
+            ("😡", "pouting face", &["angry", "rage"]),
+            ("😱", "face screaming in fear", &["scared", "shock"]),
+        ],
+    ),
+    (
+        "People",
+        &[
+            ("👍", "thumbs up", &["yes", "ok", "like"]),
+            ("👎", "thumbs down", &["no", "dislike"]),
+            ("👌", "OK hand", &["ok", "perfect"]),
+            ("✌", "victory hand", &["peace"]),
+            ("👋", "waving hand", &["hello", "bye"]),
+            ("👏", "clapping hands", &["applause", "congrats"]),
+            ("🙏", "folded hands", &["please", "thanks", "pray"]),
+            ("💪", "flexed biceps", &["strong"]),
+            ("👀", "eyes", &["look", "see"]),
+            ("👶", "baby", &["child"]),
+            ("👦", "boy", &["child"]),
+            ("👧", "girl", &["child"]),
+        ],
+    ),
+    (
+        "Animals",
+        &[
+            ("🐶", "dog face", &["dog", "pet"]),
+            ("🐱", "cat face", &["cat", "pet"]),
+            ("🐭", "mouse face", &["mouse"]),
+            ("🐰", "rabbit face", &["rabbit", "bunny"]),
+            ("🦊", "fox", &["fox"]),
+            ("🐻", "bear", &["bear"]),
+            ("🐼", "panda", &["panda"]),
+            ("🐸", "frog", &["frog"]),
+            ("🐵", "monkey face", &["monkey"]),
+            ("🐔", "chicken", &["chicken", "bird"]),
+            ("🐟", "fish", &["fish"]),
+            ("🌲", "evergreen tree", &["tree", "plant"]),
+            ("🌸", "cherry blossom", &["flower", "spring"]),
+        ],
+    ),
+    (
+        "Food",
+        &[
+            ("🍏", "green apple", &["fruit"]),
+            ("🍌", "banana", &["fruit"]),
+            ("🍓", "strawberry", &["fruit"]),
+            ("🍕", "pizza", &["food"]),
+            ("🍔", "hamburger", &["burger", "food"]),
+            ("🍟", "french fries", &["food"]),
+            ("🍰", "shortcake", &["cake", "dessert"]),
+            ("🍩", "doughnut", &["donut", "dessert"]),
+            ("☕", "hot beverage", &["coffee", "tea"]),
+            ("🍺", "beer mug", &["beer", "drink"]),
+            ("🍷", "wine glass", &["wine", "drink"]),
+        ],
+    ),
+    (
+        "Symbols",
+        &[
+            ("❤", "red heart", &["love"]),
+            ("💔", "broken heart", &["sad"]),
+            ("⭐", "star", &["favorite"]),
+            ("🔥", "fire", &["hot", "lit"]),
+            ("✨", "sparkles", &["shiny", "new"]),
+            ("🎉", "party popper", &["party", "congrats"]),
+            ("✅", "check mark button", &["done", "yes"]),
+            ("❌", "cross mark", &["no", "wrong"]),
+            ("❓", "question mark", &["question"]),
+            ("❗", "exclamation mark", &["warning"]),
+            ("💯", "hundred points", &["perfect", "score"]),
+        ],
+    ),
+];
@@ -0,0 +1,162 @@
+//! Events emitted by the keyboard with when and in which order they were emitted.
+
+use crate::Keyboard;
+use egui::{Context, Event, Id};
+
+/// Event emitted by the keyboard, see [`Keyboard::on_event`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyboardEvent {
+    /// Position of the event among all the events the keyboard emitted, increasing by one with
+    /// every event. Events are delivered in this order, whichever finger or repeating key
+    /// emitted them.
+    pub sequence: u64,
+    /// When the key emitting the event was pressed or repeated, in egui time (seconds).
+    pub time: f64,
+    /// Widget the keyboard typed into when the event was emitted, which it is meant for.
+    pub target: Option<Id>,
+    pub event: Event,
+}
+
+/// How the longer texts are typed, e.g. when pasting the clipboard or pressing a key typing
+/// a snippet, see [`Keyboard::text_batching`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TextBatching {
+    /// The whole text in one event.
+    #[default]
+    Whole,
+    /// The text split into events of at most this many characters, one per frame.
+    Chunks(usize),
+}
+
+/// What happens with the events waiting for [`Keyboard::pump_events`] when the focus moved to
+/// another widget since they were emitted, see [`Keyboard::focus_change_policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum FocusChangePolicy {
+    /// Forget the events, so they do not end up in the widget focused now.
+    #[default]
+    Drop,
+    /// Focus the widget the events are meant for again and deliver them to it.
+    Redirect,
+    /// Deliver the events to the widget focused now.
+    Deliver,
+}
+
+impl Keyboard {
+    /// Call `callback` with every event delivered by [`Keyboard::pump_events`], before egui gets
+    /// it, e.g. to measure the typing speed for velocity-sensitive features.
+    pub fn on_event(mut self, callback: impl FnMut(&KeyboardEvent) + 'static) -> Self {
+        self.on_event = Some(Box::new(callback));
+        self
+    }
+
+    /// How the longer texts are typed, e.g. in chunks for widgets or undo stacks which do not
+    /// cope well with large insertions. Every chunk takes a place in the queue of events, see
+    /// [`Keyboard::event_capacity`].
+    pub fn text_batching(mut self, batching: TextBatching) -> Self {
+        self.text_batching = batching;
+        self
+    }
+
+    /// What happens with the events waiting for [`Keyboard::pump_events`] when the focus moves to
+    /// another widget before they are delivered, e.g. when the application moves it in the same
+    /// frame as the user taps the keys.
+    pub fn focus_change_policy(mut self, policy: FocusChangePolicy) -> Self {
+        self.focus_change_policy = policy;
+        self
+    }
+
+    /// The `events` which may be delivered to the widget focused now, as set by
+    /// [`Keyboard::focus_change_policy`]. Events meant for another widget than the first one wait
+    /// for the next frame when redirected.
+    pub(crate) fn retarget_events(
+        &mut self,
+        ctx: &Context,
+        mut events: Vec<KeyboardEvent>,
+    ) -> Vec<KeyboardEvent> {
+        let Some(focused) = ctx.memory(|memory| memory.focused()) else {
+            return events;
+        };
+        let moved = |event: &KeyboardEvent| event.target.is_some_and(|target| target != focused);
+        match self.focus_change_policy {
+            FocusChangePolicy::Deliver => events,
+            FocusChangePolicy::Drop => {
+                events.retain(|event| !moved(event));
+                events
+            }
+            FocusChangePolicy::Redirect => {
+                let Some(target) = events.first().and_then(|event| event.target) else {
+                    return events;
+                };
+                if target != focused {
+                    ctx.memory_mut(|memory| memory.request_focus(target));
+                }
+                let count = events
+                    .iter()
+                    .position(|event| event.target.is_some_and(|other| other != target))
+                    .unwrap_or(events.len());
+                for event in events.drain(count..).rev() {
+                    self.events.push_front(event);
+                }
+                if !self.events.is_empty() {
+                    ctx.request_repaint();
+                }
+                events
+            }
+        }
+    }
+
+    /// The `events` with the texts split as set by [`Keyboard::text_batching`], each with whether
+    /// it has to wait for the next frame.
+    pub(crate) fn batch_text(&self, events: Vec<Event>) -> Vec<(Event, bool)> {
+        let TextBatching::Chunks(size) = self.text_batching else {
+            return events.into_iter().map(|event| (event, false)).collect();
+        };
+        let mut batched = Vec::new();
+        for event in events {
+            match event {
+                Event::Text(text) if text.chars().count() > size.max(1) => {
+                    let chars: Vec<char> = text.chars().collect();
+                    for (i, chunk) in chars.chunks(size.max(1)).enumerate() {
+                        batched.push((Event::Text(chunk.iter().collect()), i > 0));
+                    }
+                }
+                event => batched.push((event, false)),
+            }
+        }
+        batched
+    }
+
+    /// Take the queued events to deliver in this frame, up to the next chunk of a text which has
+    /// to wait for the next frame.
+    pub(crate) fn take_frame_events(&mut self, ctx: &Context) -> Vec<KeyboardEvent> {
+        // The first event waited for this frame already, the ones before it were delivered or
+        // dropped.
+        let first = self.events.front().map(|event| event.sequence);
+        self.frame_breaks
+            .retain(|sequence| first.is_some_and(|first| *sequence > first));
+        let Some(frame_break) = self.frame_breaks.front() else {
+            return std::mem::take(&mut self.events).into();
+        };
+        let count = self
+            .events
+            .iter()
+            .position(|event| event.sequence == *frame_break)
+            .unwrap_or(self.events.len());
+        ctx.request_repaint();
+        self.events.drain(..count).collect()
+    }
+
+    /// Give the `event` the next sequence number and the time of the current frame.
+    pub(crate) fn stamp(&mut self, event: Event) -> KeyboardEvent {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        KeyboardEvent {
+            sequence,
+            time: self.frame_time,
+            target: self.input_widget,
+            event,
+        }
+    }
+}
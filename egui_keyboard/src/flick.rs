@@ -0,0 +1,106 @@
+//! Swiping up on a letter to type a digit or a punctuation mark, without the long press.
+
+use crate::layouts::Row;
+use crate::{Key, KeyAction, Keyboard, LONG_PRESS_DURATION};
+use egui::{Response, Ui};
+
+/// Typed by swiping up on the keys of the top row of letters, from the left.
+const FLICK_DIGITS: [&str; 10] = ["1", "2", "3", "4", "5", "6", "7", "8", "9", "0"];
+
+/// Typed by swiping up on the keys of the bottom row of letters, from the left.
+const FLICK_PUNCTUATION: [&str; 10] = ["'", "\"", "-", "/", ":", ";", "!", "?", "(", ")"];
+
+/// Whether all the text keys of the `row` type letters, and there is at least one.
+fn is_letter_row(row: &Row) -> bool {
+    let texts: Vec<&str> = row
+        .keys
+        .iter()
+        .filter_map(|key| match key.base() {
+            Key::Text(text) => Some(text.as_ref()),
+            _ => None,
+        })
+        .collect();
+    !texts.is_empty()
+        && texts
+            .iter()
+            .all(|text| text.chars().all(char::is_alphabetic))
+}
+
+impl Keyboard {
+    /// Swipe up on a letter of the top row to type its digit, and on a letter of the bottom row
+    /// to type a common punctuation mark, e.g. `q` types `1` and `z` types `'` with QWERTY.
+    pub fn flick_gestures(mut self, enabled: bool) -> Self {
+        self.flick_gestures = enabled;
+        self
+    }
+
+    /// Find out what swiping up on the text keys of the `rows` types.
+    pub(crate) fn update_flicks(&mut self, rows: &[Row]) {
+        self.flicks.clear();
+        if !self.flick_gestures || self.layer.emoji {
+            return;
+        }
+        let letter_rows: Vec<&Row> = rows.iter().filter(|row| is_letter_row(row)).collect();
+        let (Some(&top), Some(&bottom)) = (letter_rows.first(), letter_rows.last()) else {
+            return;
+        };
+        let mut assign = |row: &Row, texts: &[&str]| {
+            let keys = row.keys.iter().filter_map(|key| match key.base() {
+                Key::Text(text) => Some(text.to_string()),
+                _ => None,
+            });
+            for (key, text) in keys.zip(texts) {
+                let text = self.digits.localize(text).into_owned();
+                self.flicks.insert(key, text);
+            }
+        };
+        assign(top, &FLICK_DIGITS);
+        if letter_rows.len() > 1 {
+            assign(bottom, &FLICK_PUNCTUATION);
+        }
+    }
+
+    /// Type what swiping up on the key typing the `text` types, once the swipe is released.
+    /// Holding the key for a long press first is not a swipe.
+    pub(crate) fn flick(&mut self, ui: &Ui, response: &Response, text: &str) {
+        if !self.flicks.contains_key(text) {
+            return;
+        }
+        let interaction = self.interaction(ui, response);
+        let time = ui.input(|input| input.time);
+        if interaction.down {
+            let pressed = self.flick_start.is_some_and(|(id, ..)| id == response.id);
+            if !pressed {
+                self.flick_start = interaction
+                    .origin
+                    .map(|origin| (response.id, origin, time - interaction.held));
+            }
+            return;
+        }
+
+        // Egui stops reporting the press on the key when the pointer moves away, so wait for
+        // the release.
+        if ui.input(|input| input.pointer.any_down()) {
+            return;
+        }
+
+        let (origin, start) = match self.flick_start {
+            Some((id, origin, start)) if id == response.id => (origin, start),
+            _ => return,
+        };
+        self.flick_start = None;
+        let duration = self.long_press_duration.unwrap_or(LONG_PRESS_DURATION);
+        if time - start >= duration {
+            return;
+        }
+        let Some(end) = interaction.pos else {
+            return;
+        };
+        let delta = end - origin;
+        if -delta.y > response.rect.height() / 2. && -delta.y > delta.x.abs() {
+            let text = self.flicks[text].clone();
+            self.perform([KeyAction::Text(text)]);
+            self.focus_back_to_input_widget(ui.ctx());
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Typing with a game controller, moving a highlight over the keys.
+
+use crate::{key_event, Key, Keyboard};
+use egui::{vec2, Event, Modifiers, Rect, Vec2};
+
+/// How much moving across the direction costs compared to moving along it, when looking for the
+/// next key. Keeps the highlight in its row when moving left or right.
+const ACROSS_WEIGHT: f32 = 2.0;
+
+/// Width of the outline of the highlighted key.
+pub(crate) const HIGHLIGHT_WIDTH: f32 = 3.0;
+
+/// Input of a game controller, see [`Keyboard::gamepad_input`]. The application maps the buttons
+/// of its controller library to these, e.g. the D-pad or the tilted stick to the directions, the
+/// south button to [`GamepadInput::Press`] and the shoulder buttons to
+/// [`GamepadInput::Backspace`] and [`GamepadInput::Space`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Press the highlighted key. Keys which have to be held to confirm what they do, see
+    /// [`crate::layouts::LayoutKey::HoldToConfirm`], stay pressed until
+    /// [`GamepadInput::Release`].
+    Press,
+    /// Release the key pressed with [`GamepadInput::Press`], if it is still held.
+    Release,
+    Backspace,
+    Space,
+}
+
+impl Keyboard {
+    /// Move the highlight over the keys or press a key with a game controller, e.g. to enter
+    /// a name in a game. The first move highlights the top left key. Call it before
+    /// [`Keyboard::show`] for every input of the controller. Egui does not know about the
+    /// controller, so the application needs to keep repainting while it is used.
+    pub fn gamepad_input(&mut self, input: GamepadInput) {
+        if self.disabled || self.key_rects.is_empty() {
+            return;
+        }
+        let direction = match input {
+            GamepadInput::Backspace => {
+                self.push_events([key_event(egui::Key::Backspace, Modifiers::NONE)]);
+                return;
+            }
+            GamepadInput::Space => {
+                self.push_events([Event::Text(" ".to_owned())]);
+                return;
+            }
+            GamepadInput::Press => {
+                if let Some(rect) = self.gamepad_key() {
+                    let held = self
+                        .key_rects
+                        .iter()
+                        .any(|(key_rect, key)| *key_rect == rect && is_held_to_confirm(key));
+                    self.queue_press(rect.center(), held);
+                }
+                return;
+            }
+            GamepadInput::Release => {
+                self.release_queued_press();
+                return;
+            }
+            GamepadInput::Up => vec2(0.0, -1.0),
+            GamepadInput::Down => vec2(0.0, 1.0),
+            GamepadInput::Left => vec2(-1.0, 0.0),
+            GamepadInput::Right => vec2(1.0, 0.0),
+        };
+        let Some(current) = self.gamepad_key() else {
+            self.gamepad_cursor = self.key_rects.first().map(|(rect, _)| rect.center());
+            return;
+        };
+        if let Some(next) = self.next_key(current, direction) {
+            self.gamepad_cursor = Some(next.center());
+        }
+    }
+
+    /// Key under the highlight, or the closest one if the keys changed under it.
+    fn gamepad_key(&self) -> Option<Rect> {
+        let cursor = self.gamepad_cursor?;
+        self.key_rects.iter().map(|(rect, _)| *rect).min_by(|a, b| {
+            a.distance_sq_to_pos(cursor)
+                .total_cmp(&b.distance_sq_to_pos(cursor))
+        })
+    }
+
+    /// Closest key from the `current` one in the `direction`.
+    fn next_key(&self, current: Rect, direction: Vec2) -> Option<Rect> {
+        let center = current.center();
+        self.key_rects
+            .iter()
+            .map(|(rect, _)| *rect)
+            .filter(|rect| *rect != current)
+            .filter_map(|rect| {
+                let offset = rect.center() - center;
+                let along = offset.dot(direction);
+                let across = (offset - along * direction).length();
+                (along > 0.0).then_some((rect, along + ACROSS_WEIGHT * across))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(rect, _)| rect)
+    }
+
+    /// Keep the highlight on a key when the keys change under it, e.g. with another layer.
+    pub(crate) fn snap_gamepad_cursor(&mut self) {
+        self.gamepad_cursor = self.gamepad_key().map(|rect| rect.center());
+    }
+
+    /// Whether the key with the `rect` is highlighted by the game controller.
+    pub(crate) fn is_gamepad_highlighted(&self, rect: Rect) -> bool {
+        self.gamepad_cursor
+            .is_some_and(|cursor| rect.contains(cursor))
+    }
+}
+
+/// Whether the `key` has to be held to confirm what it does.
+fn is_held_to_confirm(key: &Key) -> bool {
+    match key {
+        Key::HoldToConfirm(_) => true,
+        Key::Alternate { key, .. }
+        | Key::Tall { key, .. }
+        | Key::Repeat(key)
+        | Key::WithAlternates { key, .. } => is_held_to_confirm(key),
+        _ => false,
+    }
+}
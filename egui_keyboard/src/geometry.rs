@@ -0,0 +1,273 @@
+//! Sizing and placing the keys, without egui, so the math can be measured and checked directly.
+
+use crate::layouts::{Row, RowAlign};
+use crate::{compact, pages, Key, Keyboard, SelectionKey, SPACE_KEY_WIDTH};
+use egui::{pos2, vec2, Rangef, Rect, Vec2};
+
+/// Sizes and places of the keys, see [`Keyboard::compute_geometry`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyboardGeometry {
+    /// Size of a regular key.
+    pub key_size: Vec2,
+    /// Space between the keys, horizontally and vertically.
+    pub key_spacing: Vec2,
+    /// How much the keys are enlarged, see [`Keyboard::accessibility_scale`] and
+    /// [`Keyboard::display_dpi`].
+    pub scale: f32,
+    /// Labels and rects of the keys of the shown page, relative to the top left corner of the
+    /// keys.
+    pub keys: Vec<(String, Rect)>,
+    /// How many pages the layout is split into.
+    pub pages: usize,
+    /// Whether the rows are wider than the keyboard and scroll, see
+    /// [`Keyboard::scroll_wide_layouts`].
+    pub scrolled: bool,
+    /// Size taken by the rows with the spaces around them.
+    pub size: Vec2,
+}
+
+/// Sizes shared by all the rows of keys.
+pub(crate) struct KeysMetrics {
+    pub(crate) scale: f32,
+    /// The rows split into pages, see [`pages::paginate`].
+    pub(crate) pages: Vec<Vec<Row>>,
+    /// Page which is shown.
+    pub(crate) page: usize,
+    pub(crate) button_size: Vec2,
+    /// Space between the keys, horizontally and vertically.
+    pub(crate) space: Vec2,
+    /// Width of the widest row of the shown page, relative to a regular key.
+    pub(crate) widest_row: f32,
+    /// Width the rows take, wider than the keyboard when they scroll.
+    pub(crate) rows_width: f32,
+    pub(crate) scrolled: bool,
+}
+
+/// Sizes of the keys of a single row.
+pub(crate) struct RowMetrics {
+    /// Space before the first key, after the left inset.
+    pub(crate) edge_space: f32,
+    /// Width of the space keys relative to a regular key.
+    pub(crate) space_width: f32,
+    /// How much the other keys are stretched to fill a justified row without space keys.
+    pub(crate) stretch: f32,
+    /// Width the row takes.
+    pub(crate) total_width: f32,
+}
+
+impl RowMetrics {
+    /// Sizes of the keys of the `row` taking the `width`, next to the tall keys from the rows
+    /// above taking `spans_width`.
+    pub(crate) fn new(
+        row: &Row,
+        button_width: f32,
+        space: f32,
+        width: f32,
+        spans_width: f32,
+    ) -> Self {
+        let justify = row.align == RowAlign::Justify;
+        let space_keys = row
+            .keys
+            .iter()
+            .filter(|key| matches!(key.base(), Key::Space))
+            .count();
+        // Space keys are only stretched in justified rows.
+        let fixed_space_width = if justify { 0.0 } else { SPACE_KEY_WIDTH };
+        let buttons_width = row.keys.iter().map(|key| key.width_relative()).sum::<f32>()
+            + space_keys as f32 * fixed_space_width;
+        let gaps_width = row
+            .keys
+            .iter()
+            .map(|key| match key.base() {
+                Key::Gap(width) => *width,
+                _ => 0.0,
+            })
+            .sum::<f32>();
+        let offset = row.offset * button_width;
+        let total_width = offset
+            + buttons_width * button_width
+            + (row.keys.len() as f32 + 1.0) * space
+            + spans_width;
+        // Width left in the row, relative to a regular button.
+        let free_width = (width / button_width - total_width / button_width).max(0.0);
+        let (space_width, stretch) = match (justify, space_keys) {
+            (false, _) => (fixed_space_width, 1.0),
+            (true, 0) => (0.0, 1.0 + free_width / (buttons_width - gaps_width)),
+            (true, count) => (free_width / count as f32, 1.0),
+        };
+        let edge_space = offset
+            + space
+            + match row.align {
+                RowAlign::Left | RowAlign::Justify => 0.0,
+                RowAlign::Center => (width - total_width).max(0.0) / 2.0,
+                RowAlign::Right => (width - total_width).max(0.0),
+            };
+        Self {
+            edge_space,
+            space_width,
+            stretch,
+            total_width,
+        }
+    }
+
+    /// Width of the `key` relative to a regular key.
+    pub(crate) fn key_width(&self, key: &Key) -> f32 {
+        match key.base() {
+            Key::Space => self.space_width,
+            Key::Gap(width) => *width,
+            _ => key.width_relative() * self.stretch,
+        }
+    }
+}
+
+/// Where the next key of `width` starting at `x` goes, so it does not overlap the tall keys from
+/// the rows above.
+pub(crate) fn after_spans(mut x: f32, spans: &[(Rangef, usize)], width: f32, space: f32) -> f32 {
+    let mut spans: Vec<Rangef> = spans.iter().map(|(span, _)| *span).collect();
+    spans.sort_by(|a, b| a.min.total_cmp(&b.min));
+    for span in spans {
+        if span.min < x + width && x < span.max {
+            x = span.max + space;
+        }
+    }
+    x
+}
+
+impl Keyboard {
+    /// Sizes and places of the keys shown now, computed without egui, e.g. for benchmarks of the
+    /// sizing or tools previewing layouts. The keys get the `available` size, the width of the
+    /// keyboard and the height of its rows, like a docked keyboard without the suggestions above
+    /// them. The minimum physical size of the keys assumes one pixel per point, see
+    /// [`Keyboard::display_dpi`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let keyboard = egui_keyboard::Keyboard::default();
+    /// let geometry = keyboard.compute_geometry(egui::vec2(400.0, 250.0));
+    /// let key = |text: &str| geometry.keys.iter().find(|(label, _)| label == text).unwrap().1;
+    /// assert!(key("q").right() < key("w").left());
+    /// assert!(key("q").bottom() < key("a").top());
+    /// assert_eq!(geometry.pages, 1);
+    /// ```
+    pub fn compute_geometry(&self, available: Vec2) -> KeyboardGeometry {
+        let mut rows = self.rows_to_show();
+        if self.selection_row {
+            rows.insert(0, SelectionKey::ROW.to_vec().into());
+        }
+        let insets = self.safe_area_insets;
+        let width = available.x - insets.left - insets.right;
+        let metrics = self.keys_metrics(1.0, rows, width, available.y, available.x);
+        let (button_size, space) = (metrics.button_size, metrics.space);
+
+        let mut keys = Vec::new();
+        let mut spans: Vec<(Rangef, usize)> = Vec::new();
+        let mut y = space.y;
+        for row in metrics.pages[metrics.page].iter() {
+            if row.keys.is_empty() {
+                continue;
+            }
+            let spans_width = spans.iter().map(|(span, _)| span.span() + space.x).sum();
+            let row_metrics =
+                RowMetrics::new(row, button_size.x, space.x, metrics.rows_width, spans_width);
+            let mut x = insets.left + row_metrics.edge_space;
+            for (i, key) in row.keys.iter().enumerate() {
+                let key_width = button_size.x * row_metrics.key_width(key);
+                x = after_spans(x, &spans, key_width, space.x);
+                let rect = match key {
+                    Key::Tall { rows, .. } => {
+                        let rows = (*rows).max(1);
+                        let height = rows as f32 * button_size.y + (rows - 1) as f32 * space.y;
+                        let rect = Rect::from_min_size(pos2(x, y), vec2(key_width, height));
+                        spans.push((rect.x_range(), rows));
+                        rect
+                    }
+                    _ => Rect::from_min_size(pos2(x, y), vec2(key_width, button_size.y)),
+                };
+                if !matches!(key.base(), Key::Gap(_)) {
+                    keys.push((self.key_label(key, metrics.pages.len()), rect));
+                }
+                x += key_width;
+                if i + 1 < row.keys.len() {
+                    x += space.x;
+                }
+            }
+            y += button_size.y + space.y;
+
+            for (_, rows) in &mut spans {
+                *rows -= 1;
+            }
+            spans.retain(|(_, rows)| *rows > 0);
+        }
+
+        KeyboardGeometry {
+            key_size: button_size,
+            key_spacing: space,
+            scale: metrics.scale,
+            keys,
+            pages: metrics.pages.len(),
+            scrolled: metrics.scrolled,
+            size: vec2(metrics.rows_width + insets.left + insets.right, y),
+        }
+    }
+
+    /// Sizes of the keys of the `rows` taking the `width` and the `height`, on a display with
+    /// `pixels_per_point` and `screen_width` points wide.
+    pub(crate) fn keys_metrics(
+        &self,
+        pixels_per_point: f32,
+        rows: Vec<Row>,
+        width: f32,
+        height: f32,
+        screen_width: f32,
+    ) -> KeysMetrics {
+        let rows = if self.is_compact(screen_width) {
+            compact::group_keys(rows)
+        } else {
+            rows
+        };
+        let spacing = self.style.key_spacing;
+        let widest_row = |rows: &[Row]| {
+            rows.iter()
+                .map(|row| row.width_relative(spacing))
+                .reduce(f32::max)
+                .unwrap_or(0.0)
+        };
+        let full_widest_row = widest_row(&rows);
+        // The action bar takes a row of its own.
+        let extra_rows = if self.action_bar.is_some() { 1.0 } else { 0.0 };
+        let rows_count = rows.len() as f32 + extra_rows;
+        let scale = self.key_scale(pixels_per_point, rows_count, full_widest_row, width, height);
+
+        let pages = pages::paginate(rows, full_widest_row / scale, spacing);
+        let page = self.page % pages.len();
+        let widest_row = widest_row(&pages[page]);
+        let rows_count = pages.iter().map(|page| page.len()).max().unwrap_or(0) as f32 + extra_rows;
+        // Spacing between buttons = height of button * spacing
+        let button_height = scale * height / ((rows_count - 1.0) * spacing + rows_count);
+        // Widest row should have `space, button, space, button, ..., button, space` -> n+1
+        // spaces, n buttons -> (n+1)*spacing+n buttons widths = available width. Pages of a split
+        // layout might be narrower, but the buttons should not grow beyond the scale.
+        let button_width = (width / widest_row).min(scale * width / full_widest_row);
+        // Layouts too wide for usable keys scroll instead, see `Keyboard::scroll_wide_layouts`.
+        let min_width = self.scroll_min_key_width.filter(|min| button_width < *min);
+        let scrolled = min_width.is_some();
+        let button_width = min_width.unwrap_or(button_width);
+        let rows_width = if scrolled {
+            widest_row * button_width
+        } else {
+            width
+        };
+
+        KeysMetrics {
+            scale,
+            pages,
+            page,
+            button_size: vec2(button_width, button_height),
+            space: vec2(button_width * spacing, button_height * spacing),
+            widest_row,
+            rows_width,
+            scrolled,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Switching between several layouts while typing, with the globe key.
+
+use crate::layouts::KeyboardLayout;
+use crate::{alternates, KeyAction, KeyCategory, Keyboard, LayerState, Press};
+use egui::{Context, Id, Response, Ui, Vec2};
+
+/// Where the index of the selected layout is remembered in the egui memory.
+const LAYOUT_MEMORY_ID: &str = "keyboard_layout_index";
+
+impl Keyboard {
+    /// Register another layout the user can switch to while typing. Once there are two or more
+    /// layouts, the globe key is added next to the special key of layouts which do not have
+    /// it. Tapping it switches to the next layout, long-pressing it offers all of them.
+    ///
+    /// The layout set with [`Keyboard::layout`] comes first. The selected one is remembered in
+    /// the egui memory, so it survives restarts when the egui persistence is enabled.
+    pub fn add_layout(&mut self, layout: KeyboardLayout) {
+        if self.layouts.is_empty() {
+            self.layouts.push(self.keyboard_layout.clone());
+        }
+        self.layouts.push(layout);
+    }
+
+    /// Switch to the layout with given index among the ones registered with
+    /// [`Keyboard::add_layout`]. The shift and the special layer are released.
+    pub fn select_layout(&mut self, index: usize) {
+        let Some(layout) = self.layouts.get(index) else {
+            return;
+        };
+        self.keyboard_layout = layout.clone();
+        self.layer = LayerState::default();
+        self.widget_layers.clear();
+        self.page = 0;
+    }
+
+    /// Index of the current layout among the registered ones.
+    fn layout_index(&self) -> Option<usize> {
+        self.layouts
+            .iter()
+            .position(|layout| *layout == self.keyboard_layout)
+    }
+
+    /// Restore the layout selected the last time the application ran, and remember the one
+    /// selected since.
+    pub(crate) fn remember_layout(&mut self, ctx: &Context) {
+        if self.layouts.len() < 2 {
+            return;
+        }
+        let id = Id::new(LAYOUT_MEMORY_ID);
+        if !self.layout_restored {
+            self.layout_restored = true;
+            if let Some(index) = ctx.data_mut(|data| data.get_persisted::<usize>(id)) {
+                self.select_layout(index);
+            }
+        } else if let Some(index) = self.layout_index() {
+            if ctx.data_mut(|data| data.get_persisted::<usize>(id)) != Some(index) {
+                ctx.data_mut(|data| data.insert_persisted(id, index));
+            }
+        }
+    }
+
+    pub(crate) fn globe_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) -> Response {
+        let response = self.add_key_button(ui, "🌐", false, KeyCategory::Modifier, button_size);
+        match self.press(ui, &response) {
+            Press::None => {}
+            Press::Click => {
+                let next = self.layout_index().map_or(0, |index| index + 1);
+                self.select_layout(next % self.layouts.len().max(1));
+                self.focus_back_to_input_widget(ui.ctx());
+            }
+            Press::Long => {
+                let options = self
+                    .layouts
+                    .iter()
+                    .enumerate()
+                    .map(|(index, layout)| (layout.name().to_owned(), KeyAction::Layout(index)))
+                    .collect();
+                self.alternates_popup = Some(alternates::AlternatesPopup::with_actions(
+                    response.id,
+                    response.rect,
+                    options,
+                ));
+            }
+        }
+        self.slide_over_alternates(ui.ctx(), response.id);
+        response
+    }
+}
@@ -0,0 +1,67 @@
+//! Mirroring the keyboard for left-handed users.
+
+use crate::layouts::Row;
+use crate::{Key, KeyAction, Keyboard};
+
+/// Hand the user types with, see [`Keyboard::handedness`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Handedness {
+    /// Backspace and enter on the right, as the layouts define them.
+    #[default]
+    Right,
+    /// Backspace and enter on the left, the suggestions and the clipboard chip starting from
+    /// the right edge and the keyboard following a widget aligned to its right edge.
+    Left,
+}
+
+/// Whether the `key` is one of the keys which belong under the dominant hand.
+fn is_asymmetric(key: &Key) -> bool {
+    match key.base() {
+        Key::Backspace => true,
+        Key::Action { actions, .. } => matches!(
+            actions.as_slice(),
+            [KeyAction::Key {
+                key: egui::Key::Enter,
+                ..
+            }]
+        ),
+        _ => false,
+    }
+}
+
+impl Keyboard {
+    /// Mirror the backspace and the enter key, along with the suggestions and the clipboard
+    /// chip, for left-handed users. Applies to all the layouts, including the custom ones.
+    pub fn handedness(mut self, handedness: Handedness) -> Self {
+        self.handedness = handedness;
+        self
+    }
+
+    /// Change the hand the user types with, e.g. from a settings screen of the application.
+    pub fn set_handedness(&mut self, handedness: Handedness) {
+        self.handedness = handedness;
+    }
+
+    /// Move the backspace and the enter key of the `rows` to the other side of their rows for
+    /// the left-handed users, swapping them with the keys there.
+    pub(crate) fn mirror_for_handedness(&self, rows: &mut [Row]) {
+        if self.handedness == Handedness::Right {
+            return;
+        }
+        for row in rows {
+            let last = row.keys.len().saturating_sub(1);
+            let asymmetric: Vec<usize> = (0..row.keys.len())
+                .filter(|&i| is_asymmetric(&row.keys[i]))
+                .collect();
+            for &i in &asymmetric {
+                let mirrored = last - i;
+                // Two asymmetric keys mirroring each other are swapped only once.
+                if mirrored < i && asymmetric.contains(&mirrored) {
+                    continue;
+                }
+                row.keys.swap(i, mirrored);
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Keyboard shared by several screens of the application.
+
+use crate::Keyboard;
+use egui::Context;
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::Rc;
+
+/// Keyboard shared by several screens or views of the application, so they do not each
+/// construct and configure their own, e.g. with a dictionary loaded once. Clones of the handle
+/// refer to the same keyboard, the application keeps one and hands out the clones.
+///
+/// # Examples
+///
+/// ```
+/// use egui_keyboard::{Keyboard, KeyboardHandle};
+///
+/// struct LoginScreen {
+///     keyboard: KeyboardHandle,
+/// }
+///
+/// struct SearchScreen {
+///     keyboard: KeyboardHandle,
+/// }
+///
+/// let keyboard = KeyboardHandle::new(Keyboard::default());
+/// let login = LoginScreen { keyboard: keyboard.clone() };
+/// let search = SearchScreen { keyboard: keyboard.clone() };
+///
+/// login.keyboard.borrow_mut().set_numpad_companion(true);
+/// assert!(search.keyboard.borrow().config().numpad_companion);
+/// ```
+#[derive(Clone, Default)]
+pub struct KeyboardHandle(Rc<RefCell<Keyboard>>);
+
+impl KeyboardHandle {
+    pub fn new(keyboard: Keyboard) -> Self {
+        Self(Rc::new(RefCell::new(keyboard)))
+    }
+
+    /// The shared keyboard, e.g. to read its settings.
+    ///
+    /// # Panics
+    ///
+    /// If the keyboard is borrowed mutably at the same time.
+    pub fn borrow(&self) -> Ref<'_, Keyboard> {
+        self.0.borrow()
+    }
+
+    /// The shared keyboard, e.g. to change its settings.
+    ///
+    /// # Panics
+    ///
+    /// If the keyboard is borrowed at the same time, e.g. from a callback of the keyboard.
+    pub fn borrow_mut(&self) -> RefMut<'_, Keyboard> {
+        self.0.borrow_mut()
+    }
+
+    /// See [`Keyboard::pump_events`].
+    pub fn pump_events(&self, ctx: &Context) {
+        self.borrow_mut().pump_events(ctx);
+    }
+
+    /// See [`Keyboard::show`].
+    pub fn show(&self, ctx: &Context) {
+        self.borrow_mut().show(ctx);
+    }
+}
+
+impl From<Keyboard> for KeyboardHandle {
+    fn from(keyboard: Keyboard) -> Self {
+        Self::new(keyboard)
+    }
+}
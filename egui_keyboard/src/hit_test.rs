@@ -0,0 +1,66 @@
+//! Telling which key the user most likely meant, even when the press misses it slightly.
+
+use crate::Key;
+use egui::{Pos2, Rect};
+
+/// How often the letters appear in English text, in percent.
+const LETTER_FREQUENCIES: [f32; 26] = [
+    8.2, 1.5, 2.8, 4.3, 12.7, 2.2, 2.0, 6.1, 7.0, 0.15, 0.77, 4.0, 2.4, 6.7, 7.5, 1.9, 0.095, 6.0,
+    6.3, 9.1, 2.8, 0.98, 2.4, 0.15, 2.0, 0.074,
+];
+
+/// Frequency of the keys which are not letters, in percent. Same as an average letter.
+const DEFAULT_FREQUENCY: f32 = 100.0 / 26.0;
+
+/// Frequency of the space key, in percent.
+const SPACE_FREQUENCY: f32 = 15.0;
+
+/// How far the presses land from where they were aimed, relative to the key size.
+const SPREAD: f32 = 0.35;
+
+/// How much the frequency of the keys matters compared to where the press landed.
+const FREQUENCY_WEIGHT: f32 = 0.3;
+
+/// Rect of the key most likely meant by a press at `pos`, taking into account both how far the
+/// keys are and how frequently they are used. Presses outside of the rows of keys do not hit
+/// anything.
+pub(crate) fn most_likely_key(keys: &[(Rect, Key)], pos: Pos2) -> Option<Rect> {
+    let rows = keys
+        .iter()
+        .map(|(rect, _)| *rect)
+        .reduce(|union, rect| union.union(rect))?;
+    if !rows.contains(pos) {
+        return None;
+    }
+
+    keys.iter()
+        .map(|(rect, key)| (*rect, score(*rect, key, pos)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(rect, _)| rect)
+}
+
+/// Log-likelihood of the `key` being meant by a press at `pos`.
+fn score(rect: Rect, key: &Key, pos: Pos2) -> f32 {
+    // Wide keys, like space, are aimed at anywhere along their middle line.
+    let unit = egui::vec2(rect.width().min(rect.height()), rect.height());
+    let aim = Rect::from_center_size(rect.center(), rect.size() - unit);
+    let distance = (pos - aim.clamp(pos)) / unit;
+
+    FREQUENCY_WEIGHT * frequency(key).ln() - distance.length_sq() / (2.0 * SPREAD * SPREAD)
+}
+
+fn frequency(key: &Key) -> f32 {
+    match key.base() {
+        Key::Text(text) => {
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphabetic() => {
+                    LETTER_FREQUENCIES[(c.to_ascii_lowercase() as u8 - b'a') as usize]
+                }
+                _ => DEFAULT_FREQUENCY,
+            }
+        }
+        Key::Space => SPACE_FREQUENCY,
+        _ => DEFAULT_FREQUENCY,
+    }
+}
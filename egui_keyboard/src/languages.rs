@@ -0,0 +1,186 @@
+//! Suggestions of several languages at once, for users mixing them mid-sentence.
+
+use crate::suggestions::{current_word, is_word_char};
+use crate::{Keyboard, SuggestionProvider};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How many of the last finished words tell which language is being typed.
+const DETECTION_WORDS: usize = 5;
+
+/// How many words the language being typed offers for every word of the other languages.
+const DETECTED_LANGUAGE_WEIGHT: usize = 2;
+
+/// No language detected yet.
+const UNDETECTED: usize = usize::MAX;
+
+/// Blends the words of the providers of several languages, e.g. for bilingual users switching
+/// between the languages mid-sentence. The best words of all the languages are offered in
+/// turns, in the order the languages were added.
+///
+/// The language most of the last few words belong to is detected as the one being typed. Its
+/// words are offered first and twice as often, the corrections included, and it can be shown
+/// on the space key, see [`Keyboard::language_on_space`].
+///
+/// # Examples
+///
+/// ```
+/// use egui_keyboard::{FrequencyDictionary, Languages, SuggestionProvider};
+///
+/// let languages = Languages::new()
+///     .language("English", FrequencyDictionary::new([("hello", 9), ("help", 5), ("my", 1)]))
+///     .language("Deutsch", FrequencyDictionary::new([("helfen", 9), ("hell", 5), ("ich", 1)]));
+/// assert_eq!(languages.suggest("hel"), ["hello", "helfen", "help", "hell"]);
+/// assert_eq!(languages.suggest("ich hel"), ["helfen", "hell", "hello", "help"]);
+/// assert_eq!(languages.detect_language("ich hel").as_deref(), Some("Deutsch"));
+/// assert!(languages.is_word_known("helfen"));
+/// ```
+pub struct Languages {
+    languages: Vec<(String, Box<dyn SuggestionProvider + Send + Sync>)>,
+    /// Index of the language detected by the last lookup with the typed text, for the
+    /// corrections which only get the misspelled word.
+    detected: AtomicUsize,
+}
+
+impl Default for Languages {
+    fn default() -> Self {
+        Self {
+            languages: Vec::new(),
+            detected: AtomicUsize::new(UNDETECTED),
+        }
+    }
+}
+
+impl Languages {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the language with the `name`, e.g. "Čeština", offering the words of the `provider`.
+    pub fn language(
+        mut self,
+        name: impl Into<String>,
+        provider: impl SuggestionProvider + Send + Sync + 'static,
+    ) -> Self {
+        self.languages.push((name.into(), Box::new(provider)));
+        self
+    }
+
+    /// Index of the language knowing the most of the last finished words of the `text`, if it
+    /// knows any. The first one added wins a tie.
+    fn detect(&self, text: &str) -> Option<usize> {
+        let finished = &text[..text.len() - current_word(text).len()];
+        let words: Vec<&str> = finished
+            .split(|c: char| !is_word_char(c))
+            .filter(|word| !word.is_empty())
+            .rev()
+            .take(DETECTION_WORDS)
+            .collect();
+        let mut best = None;
+        let mut best_hits = 0;
+        for (i, (_, provider)) in self.languages.iter().enumerate() {
+            let hits = words
+                .iter()
+                .filter(|word| provider.is_word_known(word))
+                .count();
+            if hits > best_hits {
+                best = Some(i);
+                best_hits = hits;
+            }
+        }
+        if let Some(i) = best {
+            self.detected.store(i, Ordering::Relaxed);
+        }
+        best
+    }
+
+    /// The words of all the languages in turns, without the repeated ones. The `detected`
+    /// language goes first and takes [`DETECTED_LANGUAGE_WEIGHT`] words per turn.
+    fn blend(
+        &self,
+        detected: Option<usize>,
+        lookup: impl Fn(&dyn SuggestionProvider) -> Vec<String>,
+    ) -> Vec<String> {
+        let mut order: Vec<usize> = (0..self.languages.len()).collect();
+        if let Some(detected) = detected {
+            order.retain(|i| *i != detected);
+            order.insert(0, detected);
+        }
+        let mut lists: Vec<_> = order
+            .iter()
+            .map(|i| {
+                let weight = match Some(*i) == detected {
+                    true => DETECTED_LANGUAGE_WEIGHT,
+                    false => 1,
+                };
+                (weight, lookup(self.languages[*i].1.as_ref()).into_iter())
+            })
+            .collect();
+        let mut words: Vec<String> = Vec::new();
+        loop {
+            let mut taken = false;
+            for (weight, list) in &mut lists {
+                for word in list.take(*weight) {
+                    taken = true;
+                    if !words.contains(&word) {
+                        words.push(word);
+                    }
+                }
+            }
+            if !taken {
+                return words;
+            }
+        }
+    }
+}
+
+impl SuggestionProvider for Languages {
+    fn suggest(&self, text: &str) -> Vec<String> {
+        let detected = self.detect(text);
+        self.blend(detected, |provider| provider.suggest(text))
+    }
+
+    fn is_word_known(&self, word: &str) -> bool {
+        self.languages
+            .iter()
+            .any(|(_, provider)| provider.is_word_known(word))
+    }
+
+    fn corrections(&self, word: &str) -> Vec<String> {
+        let detected = Some(self.detected.load(Ordering::Relaxed)).filter(|i| *i != UNDETECTED);
+        self.blend(detected, |provider| provider.corrections(word))
+    }
+
+    fn detect_language(&self, text: &str) -> Option<String> {
+        self.detect(text).map(|i| self.languages[i].0.clone())
+    }
+}
+
+impl Keyboard {
+    /// Show the language being typed on the space key, as told by the
+    /// [`SuggestionProvider::detect_language`] of the [`Keyboard::suggestion_provider`], e.g. of
+    /// the [`Languages`]. It is updated after every finished word.
+    pub fn language_on_space(mut self, enabled: bool) -> Self {
+        self.language_on_space = enabled;
+        self
+    }
+
+    /// Find out which language is being typed after a word was finished.
+    pub(crate) fn update_typed_language(&mut self) {
+        let language = self
+            .suggestion_provider
+            .as_ref()
+            .filter(|_| self.language_on_space)
+            .and_then(|provider| provider.detect_language(&self.typed));
+        if language.is_some() {
+            self.typed_language = language;
+        }
+    }
+
+    /// Label of the space key.
+    pub(crate) fn space_label(&self) -> &str {
+        match &self.typed_language {
+            Some(language) if self.language_on_space => language,
+            _ => " ",
+        }
+    }
+}
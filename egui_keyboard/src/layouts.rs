@@ -1,8 +1,17 @@
-use super::Key;
+use super::{row_width_relative, Key, SPACE_BETWEEN_KEYS};
+use crate::LayerStyle;
 
+mod custom;
+
+pub use custom::{CustomLayout, KeyboardLayoutBuilder, LayoutFeature, LayoutKey, LayoutRow};
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum KeyboardLayout {
     Qwerty,
     Colemak,
+    /// Layout defined by the application, see [`KeyboardLayoutBuilder`].
+    Custom(Box<CustomLayout>),
 }
 
 impl Default for KeyboardLayout {
@@ -11,283 +20,701 @@ impl Default for KeyboardLayout {
     }
 }
 
+/// Set of keys which are shown at once, e.g. upper case letters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Layer {
+    Lowercase,
+    Uppercase,
+    Special,
+    /// Emojis from the [`crate::EmojiDataset`], offered by every layout.
+    Emoji,
+}
+
+/// How the keys of a row are placed when they do not fill the whole width of the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum RowAlign {
+    Left,
+    Center,
+    Right,
+    /// Stretch the space keys to fill the row, or all of the keys if there is no space key.
+    Justify,
+}
+
+/// Row of keys.
+#[derive(Clone)]
+pub(crate) struct Row {
+    pub(crate) keys: Vec<Key>,
+    pub(crate) align: RowAlign,
+    /// Empty space before the first key, relative to a regular key, staggering the row like on
+    /// a physical keyboard.
+    pub(crate) offset: f32,
+}
+
+impl Row {
+    pub(crate) fn align(mut self, align: RowAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub(crate) fn offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Width of the row relative to a regular key, including the offset and the `spacing` around
+    /// the keys.
+    pub(crate) fn width_relative(&self, spacing: f32) -> f32 {
+        self.offset + row_width_relative(&self.keys, spacing)
+    }
+}
+
+impl From<Vec<Key>> for Row {
+    /// Rows with a space key stretch it, the others are centered.
+    fn from(keys: Vec<Key>) -> Self {
+        let align = if keys.iter().any(|key| matches!(key.base(), Key::Space)) {
+            RowAlign::Justify
+        } else {
+            RowAlign::Center
+        };
+        Self {
+            keys,
+            align,
+            offset: 0.0,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Row {
+    type Item = &'a Key;
+    type IntoIter = std::slice::Iter<'a, Key>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.iter()
+    }
+}
+
 impl KeyboardLayout {
-    pub(crate) fn get_keys(&self, uppercase: bool, special: bool) -> Vec<Vec<Key>> {
+    /// Layers which this layout defines.
+    pub fn layers(&self) -> &'static [Layer] {
+        match self {
+            KeyboardLayout::Qwerty => &[Layer::Lowercase, Layer::Uppercase, Layer::Special],
+            KeyboardLayout::Colemak => &[Layer::Lowercase, Layer::Uppercase],
+            KeyboardLayout::Custom(layout) => layout.layers(),
+        }
+    }
+
+    pub(crate) fn layer_keys(&self, layer: Layer) -> Vec<Row> {
+        match layer {
+            Layer::Lowercase => self.get_keys(false, false),
+            Layer::Uppercase => self.get_keys(true, false),
+            Layer::Special => self.get_keys(false, true),
+            // Emojis do not depend on the layout.
+            Layer::Emoji => Vec::new(),
+        }
+    }
+
+    /// Capabilities of the keyboard the `layer` relies on, the declared ones and the ones implied
+    /// by its keys, see [`LayoutFeature`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui_keyboard::layouts::{KeyboardLayoutBuilder, Layer, LayoutFeature, LayoutKey};
+    ///
+    /// let layout = KeyboardLayoutBuilder::new()
+    ///     .row(vec![LayoutKey::text("ㄱ"), LayoutKey::text("ㅏ"), LayoutKey::Backspace])
+    ///     .row(vec![LayoutKey::Space])
+    ///     .build();
+    /// assert_eq!(layout.features(Layer::Lowercase), [LayoutFeature::InputMethod]);
+    /// ```
+    pub fn features(&self, layer: Layer) -> Vec<LayoutFeature> {
+        let mut features = match self {
+            KeyboardLayout::Custom(layout) => layout.features(layer).to_vec(),
+            _ => Vec::new(),
+        };
+        let keys = self.layer_keys(layer);
+        let implied = [
+            (
+                LayoutFeature::LongPress,
+                keys.iter().flatten().any(has_alternates),
+            ),
+            (
+                LayoutFeature::Pages,
+                keys.iter().any(|row| {
+                    row.width_relative(SPACE_BETWEEN_KEYS) > crate::lint::DEFAULT_ROW_WIDTH_BUDGET
+                }),
+            ),
+            (
+                LayoutFeature::InputMethod,
+                keys.iter().flatten().any(types_composed_text),
+            ),
+        ];
+        for (feature, implied) in implied {
+            if implied && !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+        features
+    }
+
+    /// Name of the layout offered by the globe key, see [`crate::Keyboard::add_layout`].
+    /// "Custom" for custom layouts without a name.
+    pub fn name(&self) -> &str {
+        match self {
+            KeyboardLayout::Qwerty => "QWERTY",
+            KeyboardLayout::Colemak => "Colemak",
+            KeyboardLayout::Custom(layout) => layout.name.as_deref().unwrap_or("Custom"),
+        }
+    }
+
+    /// Colors laid over the style while the `layer` is shown, see
+    /// [`KeyboardLayoutBuilder::style`].
+    pub(crate) fn layer_style(&self, layer: Layer) -> LayerStyle {
+        match self {
+            KeyboardLayout::Custom(layout) => layout.style(layer),
+            _ => LayerStyle::default(),
+        }
+    }
+
+    /// Whether the layout types a right-to-left script, see
+    /// [`KeyboardLayoutBuilder::right_to_left`].
+    pub fn is_right_to_left(&self) -> bool {
+        matches!(self, KeyboardLayout::Custom(layout) if layout.right_to_left)
+    }
+
+    /// Characters offered in a popup by a long press of the key typing the `text`, e.g. the
+    /// accented variants of a letter.
+    pub fn alternates(&self, text: &str) -> Vec<String> {
+        match self {
+            KeyboardLayout::Custom(layout) => match &layout.alternates {
+                Some(alternates) => alternates.get(text).cloned().unwrap_or_default(),
+                None => default_alternates(text),
+            },
+            _ => default_alternates(text),
+        }
+    }
+
+    pub(crate) fn get_keys(&self, uppercase: bool, special: bool) -> Vec<Row> {
         match (self, uppercase, special) {
             (KeyboardLayout::Qwerty, false, false) => qwerty(),
             (KeyboardLayout::Qwerty, true, false) => qwerty_upper(),
             (KeyboardLayout::Qwerty, _, true) => qwerty_special(),
             (KeyboardLayout::Colemak, false, _) => colemak(),
             (KeyboardLayout::Colemak, true, _) => colemak_upper(),
+            (KeyboardLayout::Custom(layout), uppercase, special) => layout.rows(uppercase, special),
         }
     }
 }
 
-pub(crate) fn qwerty() -> Vec<Vec<Key>> {
+/// Whether the `key` offers its own alternates by a long press.
+fn has_alternates(key: &Key) -> bool {
+    match key {
+        Key::WithAlternates { .. } => true,
+        Key::Alternate { key, .. }
+        | Key::Tall { key, .. }
+        | Key::Repeat(key)
+        | Key::HoldToConfirm(key) => has_alternates(key),
+        _ => false,
+    }
+}
+
+/// Whether the `key` types parts of characters which an input method composes, like dead keys
+/// with combining accents, Hangul jamo or Bopomofo.
+fn types_composed_text(key: &Key) -> bool {
+    match key {
+        Key::Text(text) | Key::Labeled { text, .. } => text.chars().any(|c| {
+            matches!(c, '\u{0300}'..='\u{036F}' | '\u{1100}'..='\u{11FF}' | '\u{3100}'..='\u{318F}')
+        }),
+        Key::Alternate { key, .. }
+        | Key::Tall { key, .. }
+        | Key::Repeat(key)
+        | Key::WithAlternates { key, .. }
+        | Key::HoldToConfirm(key) => types_composed_text(key),
+        _ => false,
+    }
+}
+
+/// Alternates offered by the built-in layouts, for the lower case letters and some symbols.
+const ALTERNATES: &[(&str, &[&str])] = &[
+    ("a", &["à", "á", "â", "ä", "ã", "å", "ą", "æ"]),
+    ("c", &["ç", "ć", "č"]),
+    ("d", &["ď"]),
+    ("e", &["è", "é", "ê", "ë", "ě", "ę"]),
+    ("i", &["ì", "í", "î", "ï"]),
+    ("l", &["ł", "ľ"]),
+    ("n", &["ñ", "ń", "ň"]),
+    ("o", &["ò", "ó", "ô", "ö", "õ", "ø", "œ"]),
+    ("r", &["ř"]),
+    ("s", &["ś", "š", "ß"]),
+    ("t", &["ť"]),
+    ("u", &["ù", "ú", "û", "ü", "ů"]),
+    ("y", &["ý", "ÿ"]),
+    ("z", &["ź", "ż", "ž"]),
+    ("?", &["¿"]),
+    ("!", &["¡"]),
+    ("-", &["–", "—"]),
+    ("$", &["€", "£", "¥"]),
+];
+
+fn default_alternates(text: &str) -> Vec<String> {
+    let lowercase = text.to_lowercase();
+    let Some((_, alternates)) = ALTERNATES.iter().find(|(key, _)| *key == lowercase) else {
+        return Vec::new();
+    };
+    alternates
+        .iter()
+        .map(|alternate| {
+            if lowercase == text {
+                alternate.to_string()
+            } else {
+                alternate.to_uppercase()
+            }
+        })
+        .collect()
+}
+
+/// Numeric pad shown for the widgets with [`crate::InputHint::Numeric`].
+pub(crate) fn numeric() -> Vec<Row> {
+    vec![
+        vec![
+            Key::text("1"),
+            Key::text("2"),
+            Key::text("3"),
+            Key::text("-"),
+        ]
+        .into(),
+        vec![
+            Key::text("4"),
+            Key::text("5"),
+            Key::text("6"),
+            Key::text("."),
+        ]
+        .into(),
+        vec![
+            Key::text("7"),
+            Key::text("8"),
+            Key::text("9"),
+            Key::text(","),
+        ]
+        .into(),
+        vec![Key::text("0"), Key::Backspace, Key::enter()].into(),
+    ]
+}
+
+/// Numeric pad with the operators, shown with [`crate::Keyboard::calculator`].
+pub(crate) fn calculator() -> Vec<Row> {
+    let operator = |label: &str, text: &str| Key::Labeled {
+        label: label.to_owned(),
+        text: text.to_owned(),
+    };
     vec![
         vec![
-            Key::Text("1"),
-            Key::Text("2"),
-            Key::Text("3"),
-            Key::Text("4"),
-            Key::Text("5"),
-            Key::Text("6"),
-            Key::Text("7"),
-            Key::Text("8"),
-            Key::Text("9"),
-            Key::Text("0"),
-        ],
+            Key::text("1"),
+            Key::text("2"),
+            Key::text("3"),
+            Key::text("+"),
+            Key::text("("),
+        ]
+        .into(),
         vec![
-            Key::Text("q"),
-            Key::Text("w"),
-            Key::Text("e"),
-            Key::Text("r"),
-            Key::Text("t"),
-            Key::Text("y"),
-            Key::Text("u"),
-            Key::Text("i"),
-            Key::Text("o"),
-            Key::Text("p"),
-        ],
+            Key::text("4"),
+            Key::text("5"),
+            Key::text("6"),
+            Key::text("-"),
+            Key::text(")"),
+        ]
+        .into(),
         vec![
-            Key::Text("a"),
-            Key::Text("s"),
-            Key::Text("d"),
-            Key::Text("f"),
-            Key::Text("g"),
-            Key::Text("h"),
-            Key::Text("j"),
-            Key::Text("k"),
-            Key::Text("l"),
-        ],
+            Key::text("7"),
+            Key::text("8"),
+            Key::text("9"),
+            operator("×", "*"),
+            operator("÷", "/"),
+        ]
+        .into(),
+        vec![
+            Key::text("."),
+            Key::text("0"),
+            Key::Backspace,
+            Key::Equals,
+            Key::enter(),
+        ]
+        .into(),
+    ]
+}
+
+/// Put the `before` and the `after` keys next to the space key, replacing the text keys which
+/// are there, e.g. the `@` and `.` keys for an email address.
+pub(crate) fn put_around_space(rows: &mut [Row], before: &str, after: &str) {
+    let Some((row, space)) = rows.iter_mut().find_map(|row| {
+        let space = row
+            .keys
+            .iter()
+            .position(|key| matches!(key.base(), Key::Space))?;
+        Some((row, space))
+    }) else {
+        return;
+    };
+
+    let is_text = |key: Option<&Key>| matches!(key, Some(Key::Text(_)));
+    if is_text(row.keys.get(space + 1)) {
+        row.keys[space + 1] = Key::text(after);
+    } else {
+        row.keys.insert(space + 1, Key::text(after));
+    }
+    match space.checked_sub(1) {
+        Some(previous) if is_text(row.keys.get(previous)) => row.keys[previous] = Key::text(before),
+        _ => row.keys.insert(space, Key::text(before)),
+    }
+}
+
+/// Left and right halves of the `rows`, for the split keyboard. Every row is divided in its
+/// middle, except for the rows with a space key, which goes to both halves. Rows stay flush
+/// with the gap between the halves.
+pub(crate) fn split_rows(rows: Vec<Row>) -> (Vec<Row>, Vec<Row>) {
+    rows.into_iter()
+        .map(|row| {
+            let space = row
+                .keys
+                .iter()
+                .position(|key| matches!(key.base(), Key::Space));
+            let (left, right) = match space {
+                Some(space) => (row.keys[..=space].to_vec(), row.keys[space..].to_vec()),
+                None => {
+                    let width: f32 = row.keys.iter().map(Key::width_relative).sum();
+                    let mut left_edge = 0.0;
+                    let middle = row
+                        .keys
+                        .iter()
+                        .position(|key| {
+                            let center = left_edge + key.width_relative() / 2.0;
+                            left_edge += key.width_relative();
+                            center >= width / 2.0
+                        })
+                        .unwrap_or(row.keys.len());
+                    (row.keys[..middle].to_vec(), row.keys[middle..].to_vec())
+                }
+            };
+            let (left_align, right_align) = match row.align {
+                RowAlign::Center => (RowAlign::Right, RowAlign::Left),
+                align => (align, align),
+            };
+            (
+                Row::from(left).align(left_align).offset(row.offset),
+                Row::from(right).align(right_align),
+            )
+        })
+        .unzip()
+}
+
+pub(crate) fn qwerty() -> Vec<Row> {
+    vec![
+        vec![
+            Key::text("1"),
+            Key::text("2"),
+            Key::text("3"),
+            Key::text("4"),
+            Key::text("5"),
+            Key::text("6"),
+            Key::text("7"),
+            Key::text("8"),
+            Key::text("9"),
+            Key::text("0"),
+        ]
+        .into(),
+        vec![
+            Key::text("q"),
+            Key::text("w"),
+            Key::text("e"),
+            Key::text("r"),
+            Key::text("t"),
+            Key::text("y"),
+            Key::text("u"),
+            Key::text("i"),
+            Key::text("o"),
+            Key::text("p"),
+        ]
+        .into(),
+        vec![
+            Key::text("a"),
+            Key::text("s"),
+            Key::text("d"),
+            Key::text("f"),
+            Key::text("g"),
+            Key::text("h"),
+            Key::text("j"),
+            Key::text("k"),
+            Key::text("l"),
+        ]
+        .into(),
         vec![
             Key::Upper,
-            Key::Text("z"),
-            Key::Text("x"),
-            Key::Text("c"),
-            Key::Text("v"),
-            Key::Text("b"),
-            Key::Text("n"),
-            Key::Text("m"),
+            Key::text("z"),
+            Key::text("x"),
+            Key::text("c"),
+            Key::text("v"),
+            Key::text("b"),
+            Key::text("n"),
+            Key::text("m"),
             Key::Backspace,
-        ],
-        vec![Key::Special, Key::Text(","), Key::Space, Key::Text(".")],
+        ]
+        .into(),
+        vec![
+            Key::Special,
+            Key::text(","),
+            Key::Space,
+            Key::text("."),
+            Key::enter(),
+        ]
+        .into(),
     ]
 }
 
-pub(crate) fn qwerty_upper() -> Vec<Vec<Key>> {
+pub(crate) fn qwerty_upper() -> Vec<Row> {
     vec![
         vec![
-            Key::Text("1"),
-            Key::Text("2"),
-            Key::Text("3"),
-            Key::Text("4"),
-            Key::Text("5"),
-            Key::Text("6"),
-            Key::Text("7"),
-            Key::Text("8"),
-            Key::Text("9"),
-            Key::Text("0"),
-        ],
+            Key::text("1"),
+            Key::text("2"),
+            Key::text("3"),
+            Key::text("4"),
+            Key::text("5"),
+            Key::text("6"),
+            Key::text("7"),
+            Key::text("8"),
+            Key::text("9"),
+            Key::text("0"),
+        ]
+        .into(),
         vec![
-            Key::Text("Q"),
-            Key::Text("W"),
-            Key::Text("E"),
-            Key::Text("R"),
-            Key::Text("T"),
-            Key::Text("Y"),
-            Key::Text("U"),
-            Key::Text("I"),
-            Key::Text("O"),
-            Key::Text("P"),
-        ],
+            Key::text("Q"),
+            Key::text("W"),
+            Key::text("E"),
+            Key::text("R"),
+            Key::text("T"),
+            Key::text("Y"),
+            Key::text("U"),
+            Key::text("I"),
+            Key::text("O"),
+            Key::text("P"),
+        ]
+        .into(),
         vec![
-            Key::Text("A"),
-            Key::Text("S"),
-            Key::Text("D"),
-            Key::Text("F"),
-            Key::Text("G"),
-            Key::Text("H"),
-            Key::Text("J"),
-            Key::Text("K"),
-            Key::Text("L"),
-        ],
+            Key::text("A"),
+            Key::text("S"),
+            Key::text("D"),
+            Key::text("F"),
+            Key::text("G"),
+            Key::text("H"),
+            Key::text("J"),
+            Key::text("K"),
+            Key::text("L"),
+        ]
+        .into(),
         vec![
             Key::Upper,
-            Key::Text("Z"),
-            Key::Text("X"),
-            Key::Text("C"),
-            Key::Text("V"),
-            Key::Text("B"),
-            Key::Text("N"),
-            Key::Text("M"),
+            Key::text("Z"),
+            Key::text("X"),
+            Key::text("C"),
+            Key::text("V"),
+            Key::text("B"),
+            Key::text("N"),
+            Key::text("M"),
             Key::Backspace,
-        ],
-        vec![Key::Special, Key::Text(","), Key::Space, Key::Text(".")],
+        ]
+        .into(),
+        vec![
+            Key::Special,
+            Key::text(","),
+            Key::Space,
+            Key::text("."),
+            Key::enter(),
+        ]
+        .into(),
     ]
 }
 
-pub(crate) fn qwerty_special() -> Vec<Vec<Key>> {
+pub(crate) fn qwerty_special() -> Vec<Row> {
     vec![
         vec![
-            Key::Text("1"),
-            Key::Text("2"),
-            Key::Text("3"),
-            Key::Text("4"),
-            Key::Text("5"),
-            Key::Text("6"),
-            Key::Text("7"),
-            Key::Text("8"),
-            Key::Text("9"),
-            Key::Text("0"),
-        ],
+            Key::text("1"),
+            Key::text("2"),
+            Key::text("3"),
+            Key::text("4"),
+            Key::text("5"),
+            Key::text("6"),
+            Key::text("7"),
+            Key::text("8"),
+            Key::text("9"),
+            Key::text("0"),
+        ]
+        .into(),
         vec![
-            Key::Text("+"),
-            Key::Text("="),
-            Key::Text("/"),
-            Key::Text("_"),
-            Key::Text("<"),
-            Key::Text(">"),
-            Key::Text("["),
-            Key::Text("]"),
-            Key::Text("{"),
-            Key::Text("}"),
-        ],
+            Key::text("+"),
+            Key::text("="),
+            Key::text("/"),
+            Key::text("_"),
+            Key::text("<"),
+            Key::text(">"),
+            Key::text("["),
+            Key::text("]"),
+            Key::text("{"),
+            Key::text("}"),
+        ]
+        .into(),
         vec![
-            Key::Text("!"),
-            Key::Text("@"),
-            Key::Text("#"),
-            Key::Text("$"),
-            Key::Text("%"),
-            Key::Text("^"),
-            Key::Text("&"),
-            Key::Text("*"),
-            Key::Text("("),
-            Key::Text(")"),
-        ],
+            Key::text("!"),
+            Key::text("@"),
+            Key::text("#"),
+            Key::text("$"),
+            Key::text("%"),
+            Key::text("^"),
+            Key::text("&"),
+            Key::text("*"),
+            Key::text("("),
+            Key::text(")"),
+        ]
+        .into(),
         vec![
-            Key::Text("-"),
-            Key::Text("'"),
-            Key::Text("`"),
-            Key::Text("~"),
-            Key::Text("\""),
-            Key::Text(":"),
-            Key::Text(";"),
-            Key::Text("?"),
+            Key::text("-"),
+            Key::text("'"),
+            Key::text("`"),
+            Key::text("~"),
+            Key::text("\""),
+            Key::text(":"),
+            Key::text(";"),
+            Key::text("?"),
             Key::Backspace,
-        ],
-        vec![Key::Special, Key::Text(","), Key::Space, Key::Text(".")],
+        ]
+        .into(),
+        vec![
+            Key::Special,
+            Key::text(","),
+            Key::Space,
+            Key::text("."),
+            Key::enter(),
+        ]
+        .into(),
     ]
 }
 
-pub(crate) fn colemak() -> Vec<Vec<Key>> {
+pub(crate) fn colemak() -> Vec<Row> {
     vec![
         vec![
-            Key::Text("1"),
-            Key::Text("2"),
-            Key::Text("3"),
-            Key::Text("4"),
-            Key::Text("5"),
-            Key::Text("6"),
-            Key::Text("7"),
-            Key::Text("8"),
-            Key::Text("9"),
-            Key::Text("0"),
-        ],
+            Key::text("1"),
+            Key::text("2"),
+            Key::text("3"),
+            Key::text("4"),
+            Key::text("5"),
+            Key::text("6"),
+            Key::text("7"),
+            Key::text("8"),
+            Key::text("9"),
+            Key::text("0"),
+        ]
+        .into(),
         vec![
-            Key::Text("q"),
-            Key::Text("w"),
-            Key::Text("f"),
-            Key::Text("p"),
-            Key::Text("g"),
-            Key::Text("j"),
-            Key::Text("l"),
-            Key::Text("u"),
-            Key::Text("y"),
-            Key::Text(";"),
-        ],
+            Key::text("q"),
+            Key::text("w"),
+            Key::text("f"),
+            Key::text("p"),
+            Key::text("g"),
+            Key::text("j"),
+            Key::text("l"),
+            Key::text("u"),
+            Key::text("y"),
+            Key::text(";"),
+        ]
+        .into(),
         vec![
-            Key::Text("a"),
-            Key::Text("r"),
-            Key::Text("s"),
-            Key::Text("t"),
-            Key::Text("d"),
-            Key::Text("h"),
-            Key::Text("n"),
-            Key::Text("e"),
-            Key::Text("i"),
-            Key::Text("o"),
-        ],
+            Key::text("a"),
+            Key::text("r"),
+            Key::text("s"),
+            Key::text("t"),
+            Key::text("d"),
+            Key::text("h"),
+            Key::text("n"),
+            Key::text("e"),
+            Key::text("i"),
+            Key::text("o"),
+        ]
+        .into(),
         vec![
             Key::Upper,
-            Key::Text("z"),
-            Key::Text("x"),
-            Key::Text("c"),
-            Key::Text("v"),
-            Key::Text("b"),
-            Key::Text("k"),
-            Key::Text("m"),
-            Key::Text(","),
-            Key::Text("."),
-            Key::Text("/"),
+            Key::text("z"),
+            Key::text("x"),
+            Key::text("c"),
+            Key::text("v"),
+            Key::text("b"),
+            Key::text("k"),
+            Key::text("m"),
+            Key::text(","),
+            Key::text("."),
+            Key::text("/"),
             Key::Backspace,
-        ],
-        vec![Key::Text(" ")],
+        ]
+        .into(),
+        vec![Key::Space, Key::enter()].into(),
     ]
 }
 
-pub(crate) fn colemak_upper() -> Vec<Vec<Key>> {
+pub(crate) fn colemak_upper() -> Vec<Row> {
     vec![
         vec![
-            Key::Text("!"),
-            Key::Text("@"),
-            Key::Text("#"),
-            Key::Text("$"),
-            Key::Text("%"),
-            Key::Text("^"),
-            Key::Text("&"),
-            Key::Text("*"),
-            Key::Text("("),
-            Key::Text(")"),
-        ],
+            Key::text("!"),
+            Key::text("@"),
+            Key::text("#"),
+            Key::text("$"),
+            Key::text("%"),
+            Key::text("^"),
+            Key::text("&"),
+            Key::text("*"),
+            Key::text("("),
+            Key::text(")"),
+        ]
+        .into(),
         vec![
-            Key::Text("Q"),
-            Key::Text("W"),
-            Key::Text("F"),
-            Key::Text("P"),
-            Key::Text("G"),
-            Key::Text("J"),
-            Key::Text("L"),
-            Key::Text("U"),
-            Key::Text("Y"),
-            Key::Text(";"),
-        ],
+            Key::text("Q"),
+            Key::text("W"),
+            Key::text("F"),
+            Key::text("P"),
+            Key::text("G"),
+            Key::text("J"),
+            Key::text("L"),
+            Key::text("U"),
+            Key::text("Y"),
+            Key::text(";"),
+        ]
+        .into(),
         vec![
-            Key::Text("A"),
-            Key::Text("R"),
-            Key::Text("S"),
-            Key::Text("T"),
-            Key::Text("D"),
-            Key::Text("H"),
-            Key::Text("N"),
-            Key::Text("E"),
-            Key::Text("I"),
-            Key::Text("O"),
-        ],
+            Key::text("A"),
+            Key::text("R"),
+            Key::text("S"),
+            Key::text("T"),
+            Key::text("D"),
+            Key::text("H"),
+            Key::text("N"),
+            Key::text("E"),
+            Key::text("I"),
+            Key::text("O"),
+        ]
+        .into(),
         vec![
             Key::Upper,
-            Key::Text("Z"),
-            Key::Text("X"),
-            Key::Text("C"),
-            Key::Text("V"),
-            Key::Text("B"),
-            Key::Text("K"),
-            Key::Text("M"),
-            Key::Text("<"),
-            Key::Text(">"),
-            Key::Text("?"),
+            Key::text("Z"),
+            Key::text("X"),
+            Key::text("C"),
+            Key::text("V"),
+            Key::text("B"),
+            Key::text("K"),
+            Key::text("M"),
+            Key::text("<"),
+            Key::text(">"),
+            Key::text("?"),
             Key::Backspace,
-        ],
-        vec![Key::Text(" ")],
+        ]
+        .into(),
+        vec![Key::Space, Key::enter()].into(),
     ]
 }
@@ -0,0 +1,638 @@
+//! Layouts defined by the application.
+
+use super::{KeyboardLayout, Layer, Row, RowAlign};
+use crate::{Key, KeyAction, LayerStyle};
+use egui::Modifiers;
+use std::collections::HashMap;
+
+/// Key of a [`CustomLayout`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LayoutKey {
+    /// Types the text, which is also its label.
+    Text(String),
+    /// Shows the `label`, but types the `text`, e.g. shows "—" and types "--".
+    Labeled {
+        label: String,
+        text: String,
+    },
+    /// Shows the `label` and presses the `key`, e.g. Enter or Tab.
+    Key {
+        label: String,
+        key: egui::Key,
+        #[cfg_attr(feature = "serde", serde(default))]
+        modifiers: Modifiers,
+    },
+    /// Stretched to fill the rest of its row, unless the row is aligned otherwise.
+    Space,
+    Backspace,
+    /// Switches between the lowercase and the uppercase layer.
+    Shift,
+    /// Switches to the special layer and back, long press locks the special layer.
+    Special,
+    /// Switches to the emoji layer, see [`crate::EmojiDataset`].
+    Emoji,
+    /// Switches to the next layout registered with [`crate::Keyboard::add_layout`], long press
+    /// offers all of them.
+    Globe,
+    /// Empty space as wide as the given number of regular keys.
+    Gap(f32),
+    /// Spans `rows` rows downwards. Keys of the rows below skip the space it takes.
+    Tall {
+        key: Box<LayoutKey>,
+        rows: usize,
+    },
+    /// Keeps typing or pressing what `key` does while it is held, see
+    /// [`crate::Keyboard::key_repeat`]. The backspace and the space keys always repeat.
+    Repeat(Box<LayoutKey>),
+    /// Offers the `alternates` by a long press of `key`, e.g. other brackets under "(" or
+    /// fractions under the digits. They take precedence over the alternates given with
+    /// [`KeyboardLayoutBuilder::alternates`].
+    WithAlternates {
+        key: Box<LayoutKey>,
+        alternates: Vec<String>,
+    },
+    /// Does what `key` does only once it is held for [`crate::Keyboard::confirm_duration`],
+    /// e.g. the Enter key submitting an order in a kiosk, so it is not pressed by accident.
+    /// A ring around the label fills up while it is held.
+    HoldToConfirm(Box<LayoutKey>),
+    /// Looks like `key`, but does what `alternate` does while the `layer` is active, e.g. the
+    /// backspace deleting forward while shift is latched or held. Only the keys typing text or
+    /// pressing a key do something as the `alternate`.
+    Alternate {
+        key: Box<LayoutKey>,
+        layer: Layer,
+        alternate: Box<LayoutKey>,
+    },
+}
+
+impl LayoutKey {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+
+    /// Key pressing the `key`, e.g. Enter, Tab, an arrow, Home or End, labeled with its symbol.
+    pub fn key(key: egui::Key) -> Self {
+        let label = match key {
+            egui::Key::Enter => "⏎",
+            egui::Key::Tab => "⇥",
+            egui::Key::ArrowLeft => "←",
+            egui::Key::ArrowRight => "→",
+            egui::Key::ArrowUp => "↑",
+            egui::Key::ArrowDown => "↓",
+            egui::Key::Home => "⇱",
+            egui::Key::End => "⇲",
+            key => key.name(),
+        };
+        Self::Key {
+            label: label.to_owned(),
+            key,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    /// Key pressing the `key` with the `modifiers`, e.g. Ctrl+A selecting all the text, labeled
+    /// with the shortcut. Ctrl+C, Ctrl+X and Ctrl+V copy, cut and paste like they do on a hardware
+    /// keyboard.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui::{Key, Modifiers};
+    /// use egui_keyboard::layouts::{KeyboardLayoutBuilder, LayoutKey};
+    ///
+    /// let layout = KeyboardLayoutBuilder::new()
+    ///     .row(vec![
+    ///         LayoutKey::chord(Modifiers::COMMAND, Key::A),
+    ///         LayoutKey::chord(Modifiers::COMMAND, Key::C),
+    ///         LayoutKey::chord(Modifiers::COMMAND, Key::V),
+    ///     ])
+    ///     .row(vec![
+    ///         LayoutKey::key(Key::ArrowLeft),
+    ///         LayoutKey::Space,
+    ///         LayoutKey::key(Key::ArrowRight),
+    ///         LayoutKey::key(Key::Enter),
+    ///     ])
+    ///     .build();
+    /// ```
+    pub fn chord(modifiers: Modifiers, key: egui::Key) -> Self {
+        let shortcut = egui::KeyboardShortcut::new(modifiers, key);
+        Self::Key {
+            label: shortcut.format(&egui::ModifierNames::NAMES, false),
+            key,
+            modifiers,
+        }
+    }
+
+    /// The same key, offering the `alternates` by a long press.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui_keyboard::layouts::{KeyboardLayoutBuilder, LayoutKey};
+    ///
+    /// let layout = KeyboardLayoutBuilder::new()
+    ///     .row(vec![
+    ///         LayoutKey::text("(").with_alternates(["[", "{", "<"]),
+    ///         LayoutKey::text(")").with_alternates(["]", "}", ">"]),
+    ///         LayoutKey::text("1").with_alternates(["½", "⅓", "¼"]),
+    ///     ])
+    ///     .build();
+    /// ```
+    pub fn with_alternates(self, alternates: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::WithAlternates {
+            key: Box::new(self),
+            alternates: alternates.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// The same key, doing what it does only once it is held, see [`LayoutKey::HoldToConfirm`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui::Key;
+    /// use egui_keyboard::layouts::{KeyboardLayoutBuilder, LayoutKey, LayoutRow};
+    ///
+    /// let layout = KeyboardLayoutBuilder::new()
+    ///     .row(LayoutRow::text("1234567890"))
+    ///     .row(vec![LayoutKey::Backspace, LayoutKey::key(Key::Enter).hold_to_confirm()])
+    ///     .build();
+    /// ```
+    pub fn hold_to_confirm(self) -> Self {
+        Self::HoldToConfirm(Box::new(self))
+    }
+
+    /// The same key, doing what the `alternate` does instead while the `layer` is active, see
+    /// [`LayoutKey::Alternate`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui::{Key, Modifiers};
+    /// use egui_keyboard::layouts::{KeyboardLayoutBuilder, Layer, LayoutKey, LayoutRow};
+    ///
+    /// let layout = KeyboardLayoutBuilder::new()
+    ///     .row(LayoutRow::text("qwertyuiop"))
+    ///     .row(vec![
+    ///         LayoutKey::Shift,
+    ///         LayoutKey::Space,
+    ///         LayoutKey::Backspace.on_layer(Layer::Uppercase, LayoutKey::key(Key::Delete)),
+    ///     ])
+    ///     .row(vec![LayoutKey::Space.on_layer(
+    ///         Layer::Uppercase,
+    ///         LayoutKey::chord(Modifiers::COMMAND, Key::Space),
+    ///     )])
+    ///     .build();
+    /// assert!(layout.lint().is_empty());
+    /// ```
+    pub fn on_layer(self, layer: Layer, alternate: LayoutKey) -> Self {
+        Self::Alternate {
+            key: Box::new(self),
+            layer,
+            alternate: Box::new(alternate),
+        }
+    }
+
+    /// What the key does as the alternate of another one, see [`LayoutKey::Alternate`].
+    fn alternate_actions(&self) -> Vec<KeyAction> {
+        match self {
+            Self::Text(text) | Self::Labeled { text, .. } => vec![KeyAction::Text(text.clone())],
+            Self::Key { key, modifiers, .. } => vec![KeyAction::Key {
+                key: *key,
+                modifiers: *modifiers,
+            }],
+            Self::Space => vec![KeyAction::Text(" ".to_owned())],
+            Self::Backspace => vec![KeyAction::Key {
+                key: egui::Key::Backspace,
+                modifiers: Modifiers::NONE,
+            }],
+            Self::Tall { key, .. }
+            | Self::Repeat(key)
+            | Self::WithAlternates { key, .. }
+            | Self::HoldToConfirm(key)
+            | Self::Alternate { key, .. } => key.alternate_actions(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The same key typing upper case text.
+    fn to_uppercase(&self) -> Self {
+        match self {
+            Self::Text(text) => Self::Text(text.to_uppercase()),
+            Self::Labeled { label, text } => Self::Labeled {
+                label: label.to_uppercase(),
+                text: text.to_uppercase(),
+            },
+            Self::Tall { key, rows } => Self::Tall {
+                key: Box::new(key.to_uppercase()),
+                rows: *rows,
+            },
+            Self::Repeat(key) => Self::Repeat(Box::new(key.to_uppercase())),
+            Self::HoldToConfirm(key) => Self::HoldToConfirm(Box::new(key.to_uppercase())),
+            Self::Alternate {
+                key,
+                layer,
+                alternate,
+            } => Self::Alternate {
+                key: Box::new(key.to_uppercase()),
+                layer: *layer,
+                alternate: alternate.clone(),
+            },
+            Self::WithAlternates { key, alternates } => Self::WithAlternates {
+                key: Box::new(key.to_uppercase()),
+                alternates: alternates.iter().map(|text| text.to_uppercase()).collect(),
+            },
+            key => key.clone(),
+        }
+    }
+}
+
+impl From<&LayoutKey> for Key {
+    fn from(key: &LayoutKey) -> Self {
+        match key {
+            LayoutKey::Text(text) => Key::Text(text.clone()),
+            LayoutKey::Labeled { label, text } => Key::Labeled {
+                label: label.clone(),
+                text: text.clone(),
+            },
+            LayoutKey::Key {
+                label,
+                key,
+                modifiers,
+            } => Key::Action {
+                label: label.clone(),
+                actions: vec![KeyAction::Key {
+                    key: *key,
+                    modifiers: *modifiers,
+                }],
+            },
+            LayoutKey::Space => Key::Space,
+            LayoutKey::Backspace => Key::Backspace,
+            LayoutKey::Shift => Key::Upper,
+            LayoutKey::Special => Key::Special,
+            LayoutKey::Emoji => Key::EmojiLayer,
+            LayoutKey::Globe => Key::Globe,
+            LayoutKey::Gap(width) => Key::Gap(*width),
+            LayoutKey::Tall { key, rows } => Key::Tall {
+                key: Box::new(key.as_ref().into()),
+                rows: *rows,
+            },
+            LayoutKey::Repeat(key) => Key::Repeat(Box::new(key.as_ref().into())),
+            LayoutKey::HoldToConfirm(key) => Key::HoldToConfirm(Box::new(key.as_ref().into())),
+            LayoutKey::WithAlternates { key, alternates } => Key::WithAlternates {
+                key: Box::new(key.as_ref().into()),
+                alternates: alternates.clone(),
+            },
+            LayoutKey::Alternate {
+                key,
+                layer,
+                alternate,
+            } => Key::Alternate {
+                key: Box::new(key.as_ref().into()),
+                layer: *layer,
+                actions: alternate.alternate_actions(),
+            },
+        }
+    }
+}
+
+/// Capability of the keyboard which a layer of a [`CustomLayout`] relies on, so
+/// [`crate::Keyboard::validate`] can tell when the keyboard is configured without it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LayoutFeature {
+    /// Characters offered by a long press, which [`crate::Keyboard::gestures`] turns off. Implied
+    /// by the [`LayoutKey::WithAlternates`] keys.
+    LongPress,
+    /// Rows too wide to show at once, e.g. a character map, split across pages when the keys
+    /// are enlarged with [`crate::Keyboard::accessibility_scale`] or
+    /// [`crate::Keyboard::display_dpi`]. Implied by the rows wider than
+    /// [`crate::lint::DEFAULT_ROW_WIDTH_BUDGET`].
+    Pages,
+    /// Keys composing the text with an input method of the application, see
+    /// [`crate::KeyMode::Custom`]. Implied by the keys typing combining accents, Hangul jamo or
+    /// Bopomofo.
+    InputMethod,
+}
+
+/// Row of a [`CustomLayout`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct LayoutRow {
+    pub keys: Vec<LayoutKey>,
+    /// Rows with a space key are justified, the others are centered, if not set.
+    pub align: Option<RowAlign>,
+    /// Empty space before the first key, relative to a regular key, staggering the row like on
+    /// a physical keyboard.
+    pub offset: f32,
+}
+
+impl LayoutRow {
+    pub fn new(keys: impl IntoIterator<Item = LayoutKey>) -> Self {
+        Self {
+            keys: keys.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Row of text keys, one for every character of the `text`.
+    pub fn text(text: &str) -> Self {
+        Self::new(text.chars().map(|c| LayoutKey::Text(c.to_string())))
+    }
+
+    pub fn align(mut self, align: RowAlign) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    pub fn offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl From<Vec<LayoutKey>> for LayoutRow {
+    fn from(keys: Vec<LayoutKey>) -> Self {
+        Self::new(keys)
+    }
+}
+
+impl From<&LayoutRow> for Row {
+    fn from(row: &LayoutRow) -> Self {
+        let mut converted = Row::from(row.keys.iter().map(Key::from).collect::<Vec<_>>());
+        if let Some(align) = row.align {
+            converted = converted.align(align);
+        }
+        converted.offset(row.offset)
+    }
+}
+
+/// Layout defined by the application, see [`KeyboardLayoutBuilder`].
+///
+/// With the `serde` feature, the layout can be loaded from a file, see
+/// [`KeyboardLayout::from_ron`].
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct CustomLayout {
+    pub lowercase: Vec<LayoutRow>,
+    /// The lowercase rows with the text in upper case if not set.
+    pub uppercase: Option<Vec<LayoutRow>>,
+    /// Rows shown by the [`LayoutKey::Special`] key. The layout has no special layer if not set.
+    pub special: Option<Vec<LayoutRow>>,
+    /// Characters offered by a long press of the key typing the text, see
+    /// [`KeyboardLayout::alternates`]. The accented letters of the built-in layouts if not set.
+    pub alternates: Option<HashMap<String, Vec<String>>>,
+    /// Name offered by the globe key, see [`KeyboardLayout::name`].
+    pub name: Option<String>,
+    /// Capabilities of the keyboard the layers rely on, see [`KeyboardLayoutBuilder::requires`].
+    pub features: HashMap<Layer, Vec<LayoutFeature>>,
+    /// Whether the layout types a right-to-left script, see
+    /// [`KeyboardLayoutBuilder::right_to_left`].
+    pub right_to_left: bool,
+    /// Colors laid over the style while the layers are shown, see
+    /// [`KeyboardLayoutBuilder::style`].
+    pub styles: HashMap<Layer, LayerStyle>,
+}
+
+impl CustomLayout {
+    pub(crate) fn layers(&self) -> &'static [Layer] {
+        if self.special.is_some() {
+            &[Layer::Lowercase, Layer::Uppercase, Layer::Special]
+        } else {
+            &[Layer::Lowercase, Layer::Uppercase]
+        }
+    }
+
+    pub(crate) fn rows(&self, uppercase: bool, special: bool) -> Vec<Row> {
+        let convert = |rows: &[LayoutRow]| rows.iter().map(Row::from).collect();
+        match (&self.uppercase, &self.special) {
+            (_, Some(rows)) if special => convert(rows),
+            (Some(rows), _) if uppercase => convert(rows),
+            (None, _) if uppercase => self
+                .lowercase
+                .iter()
+                .map(|row| {
+                    Row::from(&LayoutRow {
+                        keys: row.keys.iter().map(LayoutKey::to_uppercase).collect(),
+                        ..row.clone()
+                    })
+                })
+                .collect(),
+            _ => convert(&self.lowercase),
+        }
+    }
+
+    /// Declared capabilities the `layer` relies on. The derived uppercase layer relies on the
+    /// same ones as the lowercase one.
+    pub(crate) fn features(&self, layer: Layer) -> &[LayoutFeature] {
+        self.features
+            .get(&self.defining_layer(layer))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Colors laid over the style while the `layer` is shown. The derived uppercase layer looks
+    /// like the lowercase one.
+    pub(crate) fn style(&self, layer: Layer) -> LayerStyle {
+        self.styles
+            .get(&self.defining_layer(layer))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Layer whose rows the `layer` is derived from.
+    fn defining_layer(&self, layer: Layer) -> Layer {
+        match layer {
+            Layer::Uppercase if self.uppercase.is_none() => Layer::Lowercase,
+            layer => layer,
+        }
+    }
+}
+
+/// Builds a [`KeyboardLayout`] from rows of keys. Rows are added to the lowercase layer until
+/// another layer is chosen.
+///
+/// # Examples
+///
+/// ```
+/// use egui_keyboard::layouts::{KeyboardLayoutBuilder, LayoutKey, LayoutRow};
+///
+/// let layout = KeyboardLayoutBuilder::new()
+///     .row(LayoutRow::text("1234567890"))
+///     .row(LayoutRow::text("qwertzuiop"))
+///     .row(LayoutRow::text("asdfghjkl").offset(0.5))
+///     .row(vec![
+///         LayoutKey::Shift,
+///         LayoutKey::text("y"),
+///         LayoutKey::text("x"),
+///         LayoutKey::text("c"),
+///         LayoutKey::text("v"),
+///         LayoutKey::text("b"),
+///         LayoutKey::text("n"),
+///         LayoutKey::text("m"),
+///         LayoutKey::Backspace,
+///     ])
+///     .row(vec![LayoutKey::Special, LayoutKey::Space])
+///     .special()
+///     .row(LayoutRow::text("+ěščřžýáíé"))
+///     .row(vec![LayoutKey::Special, LayoutKey::Space, LayoutKey::Backspace])
+///     .alternates("e", ["é", "ě"])
+///     .build();
+/// assert!(layout.lint().is_empty());
+/// assert_eq!(layout.alternates("e"), ["é", "ě"]);
+/// ```
+pub struct KeyboardLayoutBuilder {
+    layout: CustomLayout,
+    layer: Layer,
+}
+
+impl Default for KeyboardLayoutBuilder {
+    fn default() -> Self {
+        Self {
+            layout: CustomLayout::default(),
+            layer: Layer::Lowercase,
+        }
+    }
+}
+
+impl KeyboardLayoutBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the following rows to the lowercase layer.
+    pub fn lowercase(mut self) -> Self {
+        self.layer = Layer::Lowercase;
+        self
+    }
+
+    /// Add the following rows to the uppercase layer, instead of deriving it from the lowercase
+    /// one.
+    pub fn uppercase(mut self) -> Self {
+        self.layer = Layer::Uppercase;
+        self.layout.uppercase.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Add the following rows to the special layer.
+    pub fn special(mut self) -> Self {
+        self.layer = Layer::Special;
+        self.layout.special.get_or_insert_with(Vec::new);
+        self
+    }
+
+    pub fn row(mut self, row: impl Into<LayoutRow>) -> Self {
+        let rows = match self.layer {
+            Layer::Uppercase => self.layout.uppercase.get_or_insert_with(Vec::new),
+            Layer::Special => self.layout.special.get_or_insert_with(Vec::new),
+            Layer::Lowercase | Layer::Emoji => &mut self.layout.lowercase,
+        };
+        rows.push(row.into());
+        self
+    }
+
+    /// Offer the `alternates` by a long press of the key typing the `text`. Only the alternates
+    /// given this way are offered, not the accented letters of the built-in layouts.
+    pub fn alternates(
+        mut self,
+        text: impl Into<String>,
+        alternates: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.layout
+            .alternates
+            .get_or_insert_with(HashMap::new)
+            .insert(
+                text.into(),
+                alternates.into_iter().map(Into::into).collect(),
+            );
+        self
+    }
+
+    /// Declare that the current layer relies on the `feature` of the keyboard, see
+    /// [`crate::Keyboard::validate`].
+    pub fn requires(mut self, feature: LayoutFeature) -> Self {
+        let features = self.layout.features.entry(self.layer).or_default();
+        if !features.contains(&feature) {
+            features.push(feature);
+        }
+        self
+    }
+
+    /// Colors laid over the [`crate::KeyboardStyle`] while the current layer is shown, e.g. to
+    /// tell the symbols from the letters at a glance. They take precedence over
+    /// [`crate::KeyboardStyle::layer_styles`].
+    pub fn style(mut self, style: LayerStyle) -> Self {
+        self.layout.styles.insert(self.layer, style);
+        self
+    }
+
+    /// The layout types a right-to-left script, e.g. Hebrew or Arabic. The caret keys of the
+    /// selection row and the arrow keys of the layout then move the caret the way they point,
+    /// so ⏵ moves it backward in the text.
+    pub fn right_to_left(mut self, enabled: bool) -> Self {
+        self.layout.right_to_left = enabled;
+        self
+    }
+
+    /// Name of the layout offered by the globe key, e.g. "Čeština".
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.layout.name = Some(name.into());
+        self
+    }
+
+    pub fn build(self) -> KeyboardLayout {
+        KeyboardLayout::Custom(Box::new(self.layout))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl KeyboardLayout {
+    /// Load a [`CustomLayout`] from a RON file, or a JSON one if the file has the `json`
+    /// extension.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, crate::LoadError> {
+        let path = path.as_ref();
+        let layout = if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            crate::load::from_json(&std::fs::read_to_string(path)?)?
+        } else {
+            crate::load::from_file(path)?
+        };
+        Ok(Self::Custom(Box::new(layout)))
+    }
+
+    /// Parse a [`CustomLayout`] from a RON string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let layout = egui_keyboard::layouts::KeyboardLayout::from_ron(
+    ///     r#"(
+    ///         lowercase: [
+    ///             (keys: [Text("7"), Text("8"), Text("9")]),
+    ///             (keys: [Text("4"), Text("5"), Text("6")]),
+    ///             (keys: [Text("1"), Text("2"), Text("3")]),
+    ///             (keys: [Text("0"), Backspace, Key(label: "⏎", key: Enter)]),
+    ///         ],
+    ///     )"#,
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn from_ron(ron: &str) -> Result<Self, crate::LoadError> {
+        crate::load::from_ron(ron).map(Self::Custom)
+    }
+
+    /// Parse a [`CustomLayout`] from a JSON string.
+    pub fn from_json(json: &str) -> Result<Self, crate::LoadError> {
+        crate::load::from_json(json).map(Self::Custom)
+    }
+}
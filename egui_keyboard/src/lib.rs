@@ -3,48 +3,453 @@
 // const SHIFT_CHARACTERS: [[char; 2]; 7] = [['⬆', '⇧'], ['⬆', '⬆'], ['⇧', '⇧'], ['▲', '△'], ['▲', '▲'], ['△', '△'], ['^', '^']];
 // const BACKSPACE_CHARACTERS: [char; 4] = ['⌫', '◁', '◀', '<'];
 
+mod action;
+mod alternates;
+mod autofill;
+mod background;
+mod blocked_words;
+mod bottom_row;
+mod calculator;
+mod calibration;
+mod capture;
+mod chips;
 mod clipboard;
+mod compact;
+mod composition;
+mod config;
+mod confirm;
+mod debug;
+mod digits;
+mod disabled_keys;
+mod emoji;
+mod event;
+mod flick;
+mod gamepad;
+mod geometry;
+mod globe;
+mod handedness;
+mod handle;
+mod hit_test;
+mod languages;
 pub mod layouts;
+pub mod lint;
+#[cfg(feature = "serde")]
+mod load;
+mod numpad;
+mod pages;
+mod peek;
+mod physical;
+mod placement;
+mod recording;
+mod repeat;
+#[cfg(feature = "demo_scenarios")]
+mod scenarios;
+mod scope;
+mod style;
+mod suggestions;
+mod transition;
+mod widget_config;
 
-use crate::layouts::KeyboardLayout;
+pub use action::{KeyAction, KeyMode};
+pub use autofill::AutofillStore;
+pub use bottom_row::BottomRowConfig;
+pub use chips::SuggestionKind;
+pub use clipboard::{ClipboardPermission, ClipboardPolling};
+pub use config::KeyboardConfig;
+pub use digits::Digits;
+pub use emoji::{Emoji, EmojiDataset};
+pub use event::{FocusChangePolicy, KeyboardEvent, TextBatching};
+pub use gamepad::GamepadInput;
+pub use geometry::KeyboardGeometry;
+pub use handedness::Handedness;
+pub use handle::KeyboardHandle;
+pub use languages::Languages;
+#[cfg(feature = "serde")]
+pub use load::LoadError;
+pub use placement::KeyboardPlacement;
+pub use recording::{RecordedEvent, Recording};
+#[cfg(feature = "demo_scenarios")]
+pub use scenarios::{Scenario, ScenarioError};
+pub use style::{KeyCategory, KeyColors, KeyShape, KeyboardStyle, LayerStyle};
+pub use suggestions::{FrequencyDictionary, SuggestionProvider};
+pub use transition::LayerTransition;
+pub use widget_config::{InputHint, WidgetConfig};
+
+use crate::emoji::{EMOJIS_PER_TAB, EMOJI_COLUMNS};
+use crate::layouts::{KeyboardLayout, Layer, LayoutKey, Row, RowAlign};
 use egui::{
-    vec2, Align2, Button, Context, Event, Frame, Id, Modifiers, Order, Rect, Ui, Vec2, WidgetText,
-    Window,
+    emath::TSTransform, pos2, scroll_area::ScrollBarVisibility, vec2, Align, Align2, Area, Button,
+    Color32, Context, Event, Frame, Id, KeyboardShortcut, LayerId, Layout, Modifiers, Order, Pos2,
+    Rangef, Rect, Response, RichText, ScrollArea, Sense, Stroke, TouchPhase, Ui, UiBuilder, Vec2,
+    WidgetText,
 };
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 
+#[derive(Clone)]
 enum Key {
-    Text(&'static str),
+    Text(String),
+    /// Shows the `label`, but types the `text`, e.g. shows a flag and types the country code.
+    Labeled {
+        label: String,
+        text: String,
+    },
+    /// Shows the `label` and does the `actions`, e.g. presses Enter.
+    Action {
+        label: String,
+        actions: Vec<KeyAction>,
+    },
     Backspace,
     Upper,
     Space,
-    Special
+    Special,
+    Selection(SelectionKey),
+    /// Switches to the next page of a layout which had to be split, see
+    /// [`Keyboard::accessibility_scale`].
+    Page,
+    /// Looks like `key`, but does `actions` instead while `layer` is active, e.g. deletes forward
+    /// instead of backward while shift is latched, see [`LayoutKey::Alternate`].
+    Alternate {
+        key: Box<Key>,
+        layer: Layer,
+        actions: Vec<KeyAction>,
+    },
+    /// Spans `rows` rows downwards, e.g. the tall Enter key of ISO keyboards or the plus key of
+    /// a numpad. Keys of the rows below skip the space it takes.
+    Tall {
+        key: Box<Key>,
+        rows: usize,
+    },
+    /// Empty space as wide as the given number of regular keys, e.g. between the halves of a split
+    /// layout. Never stretched.
+    Gap(f32),
+    /// Emoji with the given index in the [`EmojiDataset`].
+    Emoji(usize),
+    /// Shows the emojis of the tab with the given index, see [`EmojiDataset::tabs`].
+    EmojiTab(usize),
+    /// Switches between the emoji layer and the letters.
+    EmojiLayer,
+    /// Switches between the layouts registered with [`Keyboard::add_layout`].
+    Globe,
+    /// Reveals the focused password while held, see [`Keyboard::password_peek`].
+    Peek,
+    /// Replaces the typed expression with its result, see [`Keyboard::calculator`].
+    Equals,
+    /// Keeps doing what `key` does while it is held, like the backspace and the space keys do.
+    Repeat(Box<Key>),
+    /// Offers the `alternates` by a long press of `key`, instead of the ones of the layout.
+    WithAlternates {
+        key: Box<Key>,
+        alternates: Vec<String>,
+    },
+    /// Does what `key` does only once it is held, see [`Keyboard::confirm_duration`].
+    HoldToConfirm(Box<Key>),
+    /// Types one of the texts, chosen in a popup, see [`Keyboard::compact_width`].
+    Group(Vec<String>),
+    /// Calls [`Keyboard::on_dictation`].
+    Dictation,
+    /// Hides the keyboard, see [`BottomRowConfig::dismiss`].
+    Dismiss,
 }
 
 impl Key {
+    pub(crate) fn text(text: &str) -> Self {
+        Self::Text(text.to_owned())
+    }
+
+    pub(crate) fn enter() -> Self {
+        Self::Action {
+            label: "⏎".to_owned(),
+            actions: vec![KeyAction::Key {
+                key: egui::Key::Enter,
+                modifiers: Modifiers::NONE,
+            }],
+        }
+    }
+
     pub(crate) fn width_relative(&self) -> f32 {
         match self {
-            Self::Text(_) => 1.0,
+            Self::Text(_) | Self::Labeled { .. } | Self::Peek | Self::Equals => 1.0,
+            Self::Group(_) | Self::Dictation | Self::Dismiss => 1.0,
+            Self::Action { .. } => 1.5,
             Self::Backspace => 1.5,
             Self::Upper => 1.5,
             Self::Space => 0.0,
-            Self::Special => 1.5
+            Self::Special => 1.5,
+            Self::Page => 1.5,
+            Self::Selection(SelectionKey::SelectWord) => 2.0,
+            Self::Selection(_) => 1.0,
+            Self::Gap(width) => *width,
+            Self::Emoji(_) | Self::EmojiTab(_) => 1.0,
+            Self::EmojiLayer | Self::Globe => 1.5,
+            Self::Alternate { key, .. }
+            | Self::Tall { key, .. }
+            | Self::Repeat(key)
+            | Self::WithAlternates { key, .. }
+            | Self::HoldToConfirm(key) => key.width_relative(),
+        }
+    }
+
+    pub(crate) fn category(&self) -> KeyCategory {
+        match self.base() {
+            Self::Text(_)
+            | Self::Labeled { .. }
+            | Self::Gap(_)
+            | Self::Emoji(_)
+            | Self::Group(_) => KeyCategory::Character,
+            Self::Space => KeyCategory::Space,
+            Self::Backspace
+            | Self::Selection(_)
+            | Self::Action { .. }
+            | Self::Equals
+            | Self::Dictation
+            | Self::Dismiss => KeyCategory::Action,
+            Self::Upper
+            | Self::Special
+            | Self::Page
+            | Self::EmojiTab(_)
+            | Self::EmojiLayer
+            | Self::Globe
+            | Self::Peek => KeyCategory::Modifier,
+            Self::Alternate { key, .. }
+            | Self::Tall { key, .. }
+            | Self::Repeat(key)
+            | Self::WithAlternates { key, .. }
+            | Self::HoldToConfirm(key) => key.category(),
+        }
+    }
+
+    /// The key as it looks like, without any alternate bindings.
+    pub(crate) fn base(&self) -> &Key {
+        match self {
+            Self::Alternate { key, .. }
+            | Self::Tall { key, .. }
+            | Self::Repeat(key)
+            | Self::WithAlternates { key, .. }
+            | Self::HoldToConfirm(key) => key.base(),
+            key => key,
+        }
+    }
+}
+
+/// Keys of the row helping with text selection, see [`Keyboard::selection_row`].
+#[derive(Clone, Copy)]
+enum SelectionKey {
+    CaretLeft,
+    ExtendLeft,
+    SelectWord,
+    ExtendRight,
+    CaretRight,
+}
+
+impl SelectionKey {
+    const ROW: [Key; 5] = [
+        Key::Selection(Self::CaretLeft),
+        Key::Selection(Self::ExtendLeft),
+        Key::Selection(Self::SelectWord),
+        Key::Selection(Self::ExtendRight),
+        Key::Selection(Self::CaretRight),
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::CaretLeft => "⏴",
+            Self::ExtendLeft => "⏪",
+            Self::SelectWord => "word",
+            Self::ExtendRight => "⏩",
+            Self::CaretRight => "⏵",
+        }
+    }
+
+    fn actions(self) -> Vec<KeyAction> {
+        let key = |key, modifiers| KeyAction::Key { key, modifiers };
+        match self {
+            Self::CaretLeft => vec![key(egui::Key::ArrowLeft, Modifiers::NONE)],
+            Self::ExtendLeft => vec![key(egui::Key::ArrowLeft, Modifiers::SHIFT)],
+            // Jump to the beginning of the word and select till its end.
+            Self::SelectWord => vec![
+                key(egui::Key::ArrowLeft, Modifiers::CTRL),
+                key(egui::Key::ArrowRight, Modifiers::CTRL | Modifiers::SHIFT),
+            ],
+            Self::ExtendRight => vec![key(egui::Key::ArrowRight, Modifiers::SHIFT)],
+            Self::CaretRight => vec![key(egui::Key::ArrowRight, Modifiers::NONE)],
         }
     }
 }
 
+/// Event of pressing the `key`.
+fn key_event(key: egui::Key, modifiers: Modifiers) -> Event {
+    Event::Key {
+        key,
+        pressed: true,
+        repeat: false,
+        modifiers,
+        physical_key: None,
+    }
+}
+
 const SPACE_BETWEEN_KEYS: f32 = 1.0 / 6.0;
 
+/// Width of the space key relative to a regular key, in rows which do not stretch it.
+const SPACE_KEY_WIDTH: f32 = 4.0;
+
+/// How long a key has to be held to count as a long press, in seconds, unless set with
+/// [`Keyboard::long_press_duration`].
+const LONG_PRESS_DURATION: f64 = 0.5;
+
+/// How long the backspace, space and other repeating keys have to be held before they start
+/// repeating, in seconds, unless set with [`Keyboard::key_repeat`].
+const REPEAT_DELAY: f64 = 0.5;
+
+/// How often a held key repeats, in seconds, unless set with [`Keyboard::key_repeat`].
+const REPEAT_INTERVAL: f64 = 0.05;
+
+/// How a key was pressed.
+#[derive(PartialEq)]
+enum Press {
+    None,
+    Click,
+    Long,
+}
+
+/// What the keyboard does while the user drag-scrolls content elsewhere in the UI.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ScrollBehavior {
+    /// Keep the keyboard as it is.
+    #[default]
+    Stay,
+    /// Slide the keyboard below the screen edge until the scroll ends.
+    SlideAway,
+    /// Make the keyboard nearly transparent until the scroll ends.
+    Dim,
+}
+
+/// What happens with a key press when the queue of events waiting for [`Keyboard::pump_events`]
+/// is full, see [`Keyboard::event_capacity`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum OverflowPolicy {
+    /// Forget the oldest event to make room for the new one.
+    #[default]
+    DropOldest,
+    /// Forget the new event.
+    DropNewest,
+}
+
+type EventCallback = dyn FnMut(&Event);
+
+type KeyboardEventCallback = dyn FnMut(&KeyboardEvent);
+
+/// Called with the id of the password field and whether to reveal it.
+type PeekCallback = dyn FnMut(Id, bool);
+
+/// Shows the widgets of the row below the keys, given the size of a regular key.
+type ActionBar = dyn FnMut(&mut Ui, Vec2);
+
+/// Name of the keyboard window.
+const WINDOW_TITLE: &str = "Keyboard";
+
+/// How many previously focused widgets are remembered.
+const FOCUS_HISTORY_LENGTH: usize = 8;
+
+/// Shortcuts of a hardware keyboard accepting the suggestions, unless configured otherwise.
+const DEFAULT_SUGGESTION_SHORTCUTS: [KeyboardShortcut; 3] = [
+    KeyboardShortcut::new(Modifiers::CTRL, egui::Key::Num1),
+    KeyboardShortcut::new(Modifiers::CTRL, egui::Key::Num2),
+    KeyboardShortcut::new(Modifiers::CTRL, egui::Key::Num3),
+];
+
+/// How many events wait for [`Keyboard::pump_events`] at most, unless configured otherwise.
+const DEFAULT_EVENT_CAPACITY: usize = 256;
+
+/// Parts of the screen edges which are not usable, e.g. because of rounded corners, a notch or the
+/// home indicator.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SafeAreaInsets {
+    pub left: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+/// Skip the space taken by the tall keys from the rows above, if the next key of `width` would
+/// overlap them.
+fn skip_spans(ui: &mut Ui, spans: &[(Rangef, usize)], width: f32, spacing: f32) {
+    let x = ui.cursor().min.x;
+    let next = geometry::after_spans(x, spans, width, spacing);
+    if next > x {
+        ui.add_space(next - x);
+    }
+}
+
+/// Width of the row relative to a regular key, including the `spacing` around the keys.
+fn row_width_relative(row: &[Key], spacing: f32) -> f32 {
+    row.iter().map(|key| key.width_relative()).sum::<f32>() + (row.len() as f32 + 1.0) * spacing
+}
+
 /// Main struct for the virtual keyboard. It stores the state of the keyboard and handles the
 /// rendering. Needs to be stored between frames.
 #[derive(Default)]
 pub struct Keyboard {
     input_widget: Option<Id>,
-    events: VecDeque<Event>,
-    upper: bool,
-    special: bool,
+
+    /// Whether the input widget had focus when the keyboard was last shown.
+    input_widget_focused: bool,
+
+    /// Widgets which had focus before the input widget, the most recent last.
+    focus_history: Vec<Id>,
+    events: VecDeque<KeyboardEvent>,
+    /// Sequence number of the next event, see [`KeyboardEvent::sequence`].
+    next_sequence: u64,
+    text_batching: TextBatching,
+    /// Sequence numbers of the queued chunks of texts which wait for the next frame, see
+    /// [`Keyboard::text_batching`].
+    frame_breaks: VecDeque<u64>,
+    /// Egui time of the frame in which the keyboard was last shown, for the events.
+    frame_time: f64,
+    /// Called with every event delivered by [`Keyboard::pump_events`].
+    on_event: Option<Box<KeyboardEventCallback>>,
+
+    /// How many events can wait for [`Keyboard::pump_events`]. [`DEFAULT_EVENT_CAPACITY`] if not
+    /// set.
+    event_capacity: Option<usize>,
+    overflow_policy: OverflowPolicy,
+    focus_change_policy: FocusChangePolicy,
+    /// Called with every event dropped because the queue was full.
+    on_event_overflow: Option<Box<EventCallback>>,
+    /// Called with the events while no widget needs the keyboard, see
+    /// [`Keyboard::free_typing`].
+    free_typing: Option<Box<EventCallback>>,
+    /// Whether the keyboard is shown for the free typing, so the events go to its callback.
+    free_typing_active: bool,
+    /// Whether the keyboard leaves the egui focus alone, see [`Keyboard::manage_focus`].
+    focus_management_disabled: bool,
+    /// Layers of the widgets the keyboard types into, all if empty, see [`Keyboard::scope`].
+    scopes: Vec<Id>,
+    /// Reveals the focused password, see [`Keyboard::password_peek`].
+    password_peek: Option<Box<PeekCallback>>,
+    /// Keys of the bottom row, see [`Keyboard::bottom_row`].
+    bottom_row: BottomRowConfig,
+    /// Called by the dictation key, see [`Keyboard::on_dictation`].
+    on_dictation: Option<Box<dyn FnMut()>>,
+    /// Draws the entries of the suggestion bar, see [`Keyboard::suggestion_renderer`].
+    suggestion_renderer: Option<chips::SuggestionRenderer>,
+    /// Password field revealed while the peek key is held.
+    peeking: Option<Id>,
+    /// Row of widgets of the application below the keys, see [`Keyboard::action_bar`].
+    action_bar: Option<Box<ActionBar>>,
+    /// Where the action bar was shown in the last frame.
+    action_bar_rect: Option<Rect>,
+    /// How many times the keyboard was shown since the events were last pumped.
+    shows_since_pump: u32,
+    layer: LayerState,
     keyboard_layout: KeyboardLayout,
 
+    /// Layouts the globe key switches between, see [`Keyboard::add_layout`].
+    layouts: Vec<KeyboardLayout>,
+    /// Whether the layout selected the last time the application ran was restored.
+    layout_restored: bool,
+
     shift_characters: [char; 2],
     backspace_character: char,
 
@@ -52,8 +457,247 @@ pub struct Keyboard {
     /// hysteresis to avoid flickering.
     needed: u32,
 
-    /// Last rect where the keyboard was rendered.
-    last_rect: Option<Rect>,
+    /// Where the keyboard is shown, see [`KeyboardPlacement`].
+    placement: KeyboardPlacement,
+
+    /// Layers and rects where the keyboard was rendered in the last frame.
+    last_rects: Vec<(LayerId, Rect)>,
+
+    /// How much of the screen height, from its bottom edge, the keyboard covers, following the
+    /// show animation.
+    covered_height: f32,
+
+    /// Height of the docked panel when fully shown, to animate it.
+    docked_height: f32,
+
+    /// Rects the keyboard must stay off in this frame, see [`Keyboard::avoid`].
+    avoided: Vec<Rect>,
+
+    /// Rect the keyboard is shown next to in this frame, see [`Keyboard::follow`].
+    followed: Option<Rect>,
+
+    /// Where the presses landed, if the debug heatmap is enabled.
+    heatmap: Option<debug::Heatmap>,
+
+    /// Whether to draw the computed sizes over the keyboard.
+    debug_metrics: bool,
+
+    /// Frame of the keyboard window. Filled according to the style if not set.
+    frame: Option<Frame>,
+
+    style: KeyboardStyle,
+
+    /// Opacity of the keyboard background. Opaque if not set.
+    background_opacity: Option<f32>,
+
+    /// Color dimming the content beneath the keyboard.
+    scrim: Option<Color32>,
+
+    safe_area_insets: SafeAreaInsets,
+
+    /// How much to enlarge the keys and their labels. Not scaled if not set.
+    accessibility_scale: Option<f32>,
+    /// Narrowest keys before the layout scrolls instead of shrinking, see
+    /// [`Keyboard::scroll_wide_layouts`]. Layouts are shrunk to fit if not set.
+    scroll_min_key_width: Option<f32>,
+    /// Width of the screen below which the keys are grouped, see [`Keyboard::compact_width`].
+    /// [`compact::COMPACT_WIDTH`] if not set.
+    compact_width: Option<f32>,
+    /// Where to scroll the wide layout to in the next frame.
+    scroll_to: Option<f32>,
+    /// Physical density of the display in pixels per inch, see [`Keyboard::display_dpi`].
+    display_dpi: Option<f32>,
+    /// Smallest size of the keys in millimeters. [`physical::MIN_KEY_SIZE_MM`] if not set.
+    min_key_size_mm: Option<f32>,
+
+    /// Currently shown page of a layout split because of the accessibility scale.
+    page: usize,
+
+    scroll_behavior: ScrollBehavior,
+
+    key_mode: KeyMode,
+
+    /// How long the keyboard slides in and out, in seconds. Appears and disappears at once if
+    /// not set.
+    show_animation: Option<f32>,
+    /// How many frames the screen needs to stay in place before the keyboard is shown, see
+    /// [`Keyboard::settle_frames`].
+    settle_frames: u32,
+    settling: placement::Settling,
+    /// How the keys change when switching between the letters, the symbols and the emojis.
+    layer_transition: LayerTransition,
+    /// Which of the letters, the symbols and the emojis were shown in the last frame.
+    shown_layer_kind: Option<u8>,
+    /// When the transition to the shown layer started, and whether the keys slide in from the
+    /// right (1) or from the left (-1).
+    layer_transition_start: Option<(f64, f32)>,
+
+    /// How much of the keyboard is shown, from 0 to 1, following the show animation.
+    visibility: f32,
+
+    /// Whether to show the row helping with text selection.
+    selection_row: bool,
+
+    /// Rects of the keys rendered in the last frame.
+    key_rects: Vec<(Rect, Key)>,
+
+    /// Where the touches which are still in progress started.
+    touches: HashMap<u64, Pos2>,
+
+    /// Whether the shift key is being held down.
+    shift_held: bool,
+
+    /// Whether anything was typed with another finger while the shift key was held.
+    typed_while_shift_held: bool,
+
+    /// Key which is being long-pressed.
+    long_pressed: Option<Id>,
+
+    /// How long a key has to be held to count as a long press, in seconds.
+    /// [`LONG_PRESS_DURATION`] if not set.
+    long_press_duration: Option<f64>,
+    /// How long the keys confirming what they do have to be held, in seconds.
+    /// [`confirm::CONFIRM_DURATION`] if not set.
+    confirm_duration: Option<f64>,
+    /// Key which was held long enough to confirm, until it is released.
+    confirmed: Option<Id>,
+    /// How far the captured presses are moved, see [`Keyboard::touch_offset`].
+    touch_offset: Vec2,
+    /// Targets being tapped, see [`Keyboard::start_touch_calibration`].
+    calibration: Option<calibration::Calibration>,
+    /// Center of the key highlighted by the game controller, see [`Keyboard::gamepad_input`].
+    gamepad_cursor: Option<Pos2>,
+
+    /// Whether the long presses, the swipes and the taps of other fingers are ignored, see
+    /// [`Keyboard::gestures`].
+    gestures_disabled: bool,
+
+    /// Alternates of the long pressed key, e.g. accented letters.
+    alternates_popup: Option<alternates::AlternatesPopup>,
+
+    /// Delay before a held key starts repeating and the interval between the repeats, in
+    /// seconds. [`REPEAT_DELAY`] and [`REPEAT_INTERVAL`] if not set.
+    key_repeat: Option<(f64, f64)>,
+    /// Key which is held long enough to repeat.
+    repeating: Option<repeat::Repeating>,
+
+    widget_configs: HashMap<Id, WidgetConfig>,
+
+    /// Layer state of the widgets which had focus before.
+    widget_layers: HashMap<Id, LayerState>,
+
+    /// Rest of the text offered by the application for the widget, see
+    /// [`Keyboard::set_completion`].
+    completion: Option<(Id, String)>,
+    /// Word reported as misspelled in the widget with the given id, see
+    /// [`Keyboard::set_misspelled`].
+    misspelled: Option<(Id, String)>,
+    /// Texts offered by the application above the keys, see [`Keyboard::offer_suggestion`].
+    offered: Vec<String>,
+
+    /// Text being composed by the input method of the application for the widget, see
+    /// [`Keyboard::set_composition`].
+    composition: Option<(Id, String)>,
+
+    /// Source of the words offered above the keys, see [`Keyboard::suggestion_provider`].
+    suggestion_provider: Option<Box<dyn SuggestionProvider>>,
+    /// Words looked up in the background, see [`Keyboard::background_suggestion_provider`].
+    background_lookups: Option<background::BackgroundLookups>,
+
+    /// Text typed into the focused widget through the keyboard, for the suggestions.
+    typed: String,
+    /// Text typed by the last accepted correction and the text it replaced, see
+    /// [`Keyboard::undo_correction`].
+    correction_undo: Option<(String, String)>,
+    /// Words never offered, in lower case, see [`Keyboard::blocked_words`].
+    blocked_words: HashSet<String>,
+    /// Blocked words which the user typed in full, so they are offered after all.
+    typed_blocked_words: HashSet<String>,
+    /// Language the text is being typed in, see [`Keyboard::language_on_space`].
+    typed_language: Option<String>,
+    /// Whether the space key shows the language being typed, see
+    /// [`Keyboard::language_on_space`].
+    language_on_space: bool,
+    /// Store learning what is typed, see [`Keyboard::autofill_store`].
+    autofill_store: Option<Box<dyn AutofillStore>>,
+
+    /// Shortcuts of a hardware keyboard accepting the suggestions, the first one accepting the
+    /// first suggestion and so on. [`DEFAULT_SUGGESTION_SHORTCUTS`] if not set.
+    suggestion_shortcuts: Option<Vec<KeyboardShortcut>>,
+
+    /// Where the swipe over the space key started.
+    space_swipe_origin: Option<Pos2>,
+
+    /// Whether swiping up on a letter types a digit or a punctuation mark, see
+    /// [`Keyboard::flick_gestures`].
+    flick_gestures: bool,
+    /// What swiping up on the keys typing the texts types, for the keys shown in this frame.
+    flicks: HashMap<String, String>,
+    /// Key where the swipe up started, where and when.
+    flick_start: Option<(Id, Pos2, f64)>,
+
+    /// Whether the numeric keys evaluate the typed expression, see [`Keyboard::calculator`].
+    calculator: bool,
+    /// Whether a numpad is shown next to the keyboard, see [`Keyboard::numpad_companion`].
+    numpad_companion: bool,
+
+    clipboard: clipboard::Clipboard,
+    /// Whether the clipboard is neither offered nor pasted, see [`Keyboard::clipboard`].
+    clipboard_disabled: bool,
+    clipboard_polling: ClipboardPolling,
+
+    /// Press on the keyboard taken out of the egui input, see [`Keyboard::filter_input`].
+    captured_press: Option<capture::CapturedPress>,
+    /// Presses which landed in the same frame as the captured one, handled after it.
+    queued_presses: VecDeque<capture::CapturedPress>,
+
+    /// Hand the user types with, mirroring the keyboard for the left hand.
+    handedness: Handedness,
+
+    /// Whether to guess the key meant by an imprecise press.
+    fat_finger_compensation: bool,
+
+    /// Digits typed by the number keys.
+    digits: Digits,
+
+    /// Whether the keyboard is greyed out and ignores the presses, see
+    /// [`Keyboard::set_enabled`].
+    disabled: bool,
+    /// Keys taken out of all the layouts, see [`Keyboard::set_key_visible`].
+    hidden_keys: Vec<LayoutKey>,
+    /// Keys greyed out in all the layouts, see [`Keyboard::set_key_enabled`].
+    disabled_keys: Vec<LayoutKey>,
+
+    /// Emojis offered by the emoji layer.
+    emoji: EmojiDataset,
+
+    recording: Option<Recording>,
+    replay: Option<recording::Replay>,
+}
+
+/// Which layer is shown and how it was chosen. Remembered separately for every widget.
+#[derive(Clone, Copy, Default)]
+struct LayerState {
+    upper: bool,
+    special: bool,
+    /// Whether the special layer stays active until it is explicitly unlocked.
+    special_locked: bool,
+    /// Whether the emoji layer is shown instead of the layout.
+    emoji: bool,
+    /// Tab of the emoji layer which is shown.
+    emoji_tab: usize,
+}
+
+impl LayerState {
+    fn new(layer: Layer) -> Self {
+        Self {
+            upper: layer == Layer::Uppercase,
+            special: layer == Layer::Special,
+            special_locked: false,
+            emoji: layer == Layer::Emoji,
+            emoji_tab: 0,
+        }
+    }
 }
 
 impl Keyboard {
@@ -66,259 +710,2046 @@ impl Keyboard {
     }
 }
 
-fn heading_button(text: &str, button_size: Option<Vec2>) -> Button<'static> {
-    button(WidgetText::from(text).heading(), button_size)
-}
-
-fn button(text: impl Into<WidgetText>, button_size: Option<Vec2>) -> Button<'static> {
-    let mut button = Button::new(text).frame(true);
-    if button_size.is_none() {
-        button = button.min_size(Vec2::new(10.0, 50.0))
-    }
-    button
+/// Button of the keyboard. It can not get focus, so it is skipped when tabbing through the
+/// widgets and clicking it does not take the focus from the input widget.
+fn button(text: impl Into<WidgetText>, min_size: Vec2) -> Button<'static> {
+    Button::new(text)
+        .frame(true)
+        .min_size(min_size)
+        .sense(Sense::CLICK)
 }
 
 impl Keyboard {
     /// Inject text events into Egui context. This function needs to be called before any widget is
     /// created, otherwise the key presses will be ignored.
     pub fn pump_events(&mut self, ctx: &Context) {
-        ctx.input_mut(|input| input.events.extend(std::mem::take(&mut self.events)));
+        let events = self.take_frame_events(ctx);
+        let mut events = self.retarget_events(ctx, events);
+        for event in self.replayed_events(ctx) {
+            events.push(self.stamp(event));
+        }
+        if let Some(callback) = &mut self.on_event {
+            events.iter().for_each(callback);
+        }
+        let events: Vec<Event> = events.into_iter().map(|event| event.event).collect();
+        self.record(ctx, &events);
+        ctx.input_mut(|input| input.events.extend(events));
+        self.shows_since_pump = 0;
     }
 
-    pub fn layout(mut self, layout: KeyboardLayout) -> Self {
-        self.keyboard_layout = layout;
+    /// Events waiting for [`Keyboard::pump_events`], the oldest first.
+    pub fn pending_events(&self) -> impl ExactSizeIterator<Item = &Event> {
+        self.events.iter().map(|event| &event.event)
+    }
+
+    /// Drop the events waiting for [`Keyboard::pump_events`], e.g. when the application navigates
+    /// away while the user types, so they do not end up in whatever widget gets focus next.
+    pub fn clear_pending_events(&mut self) {
+        self.events.clear();
+    }
+
+    /// How many key presses can wait for [`Keyboard::pump_events`] at most. If the application
+    /// does not pump the events, the queue would otherwise grow without limit.
+    pub fn event_capacity(mut self, capacity: usize) -> Self {
+        self.event_capacity = Some(capacity);
+        self
+    }
+
+    /// What to do with a key press when the queue of events is full, see
+    /// [`Keyboard::event_capacity`].
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// Call `callback` with every event which is dropped because the queue of events is full,
+    /// e.g. to log a warning.
+    pub fn on_event_overflow(mut self, callback: impl FnMut(&Event) + 'static) -> Self {
+        self.on_event_overflow = Some(Box::new(callback));
         self
     }
 
-    /// Area which is free from the keyboard. This is useful when you want to constrain a window to
-    /// the area which is not covered by the keyboard.
+    /// Keep the keyboard shown even when no widget has focus, delivering the events to the
+    /// `callback` instead of the egui input while no widget needs the keyboard, e.g. for games or
+    /// custom text engines which do not use the egui focus. When a widget gets focus, the events
+    /// go to it as usual.
     ///
-    /// # Examples
+    /// The keyboard is shown whenever [`Keyboard::show`] is called, so skip the call to hide it.
+    pub fn free_typing(mut self, callback: impl FnMut(&Event) + 'static) -> Self {
+        self.free_typing = Some(Box::new(callback));
+        self
+    }
+
+    /// Whether the keyboard moves the egui focus: back to the input widget after a key press
+    /// and to the previously focused widget when a popup with the input widget closes. Turn it
+    /// off together with [`Keyboard::filter_input`], so the keyboard never touches the focus and
+    /// `Context::wants_keyboard_input` only follows the widgets of the application.
+    pub fn manage_focus(mut self, enabled: bool) -> Self {
+        self.focus_management_disabled = !enabled;
+        self
+    }
+
+    /// Show a row of widgets of the application below the keys, e.g. "Cancel" and "OK" buttons
+    /// of a kiosk dialog. The row is as tall as a row of keys and the `bar` gets the size of
+    /// a regular key, so its buttons can match the keys.
     ///
-    /// ```no_run
-    /// # egui::__run_test_ctx(|ctx| {
-    /// # let keyboard = egui_keyboard::Keyboard::default();
-    /// egui::Window::new("Hello")
-    ///   .constrain_to(keyboard.safe_rect(ctx))
-    ///   .show(ctx, |ui| {
-    ///      ui.label("it is a window");
-    ///   });
-    /// # });
     /// ```
-    pub fn safe_rect(&self, ctx: &Context) -> Rect {
-        let screen_rect = ctx.screen_rect();
+    /// let keyboard = egui_keyboard::Keyboard::default().action_bar(|ui, key_size| {
+    ///     let size = egui::vec2(key_size.x * 3.0, key_size.y);
+    ///     if ui.add_sized(size, egui::Button::new("OK")).clicked() {
+    ///         // Submit the dialog.
+    ///     }
+    /// });
+    /// ```
+    pub fn action_bar(mut self, bar: impl FnMut(&mut Ui, Vec2) + 'static) -> Self {
+        self.action_bar = Some(Box::new(bar));
+        self
+    }
 
-        if let Some(last_rect) = self.last_rect {
-            Rect::from_min_max(
-                screen_rect.min,
-                screen_rect.max - vec2(0., last_rect.height()),
-            )
-        } else {
-            screen_rect
-        }
+    /// Type the `text` as if it was typed on the keys, e.g. the code read by a barcode scanner or
+    /// the text from a remote control. It goes to the widget the keyboard types into with the next
+    /// [`Keyboard::pump_events`].
+    ///
+    /// ```
+    /// let mut keyboard = egui_keyboard::Keyboard::default();
+    /// keyboard.insert_text("4006381333931");
+    /// keyboard.send_key(egui::Key::Enter, egui::Modifiers::NONE);
+    /// assert_eq!(keyboard.pending_events().count(), 2);
+    /// ```
+    pub fn insert_text(&mut self, text: &str) {
+        self.push_events([Event::Text(text.to_owned())]);
     }
 
-    /// Shows the virtual keyboard if needed.
-    pub fn show(&mut self, ctx: &Context) {
-        self.remember_input_widget(ctx);
+    /// Press the `key` as if it was pressed on the keyboard, see [`Keyboard::insert_text`].
+    pub fn send_key(&mut self, key: egui::Key, modifiers: Modifiers) {
+        self.push_events([key_event(key, modifiers)]);
+    }
 
-        if self.keyboard_input_needed(ctx) {
-            let keys = self.keyboard_layout.get_keys(self.upper, self.special);
-
-            let response = Window::new("Keyboard")
-                .frame(Frame::NONE.fill(ctx.style().visuals.extreme_bg_color))
-                .collapsible(false)
-                .resizable(false)
-                .title_bar(false)
-                .anchor(Align2::CENTER_BOTTOM, [0., 0.])
-                .fixed_size(vec2(ctx.available_rect().width(), 0.))
-                .order(Order::Foreground)
-                .show(ctx, |ui| {
-                    // We do not want any spacing between the keys.
-                    ui.style_mut().spacing.item_spacing = Vec2::ZERO;
-
-                    let widest_row = keys.iter().map(|row| row.iter().map(|key| key.width_relative()).sum::<f32>() + (row.len() as f32 + 1.0) * SPACE_BETWEEN_KEYS).reduce(f32::max).unwrap_or(0.0);
-                    let available_height = ctx.available_rect().height();
-                    let available_width = ui.available_width();
-                    // Spacing between buttons = width of button * SPACE_BETWEEN_KEYS
-                    let rows_count = keys.len() as f32;
-                    let button_height = available_height / 3.0 / ((rows_count - 1.0) * SPACE_BETWEEN_KEYS + rows_count);
-                    let vertical_space = button_height * SPACE_BETWEEN_KEYS;
-                    // Spacing between buttons = width of button * SPACE_BETWEEN_KEYS
-                    // Widest row should have `space, button, space, button, ..., button, space` -> n+1 spaces, n buttons -> (n+1)*SPACE_BETWEEN_KEYS+n buttons widths = available width
-                    let button_width = available_width / widest_row;
-                    let horizontal_space = button_width * SPACE_BETWEEN_KEYS;
-
-                    ui.add_space(vertical_space);
-                    self.clipboard_key(ui, horizontal_space, vertical_space);
-
-                    for row in keys.iter() {
-                        if row.is_empty() {
-                            continue;
-                        }
-                        let row_buttons_width = row.iter().map(|key| key.width_relative()).sum::<f32>();
-                        let row_len = row.len() as f32;
-                        let row_total_width = row_buttons_width*button_width + (row_len + 1.0) * horizontal_space;
-                        let row_total_relative_width = row_total_width / button_width;
-                        let space_buttons_count = row.iter().filter(|key| matches!(key, Key::Space)).count();
-                        let space_relative_width = if space_buttons_count == 0 {
-                            0.0
-                        } else {
-                            (widest_row - row_total_relative_width) / (space_buttons_count as f32)
-                        };
-                        let edge_space = if space_buttons_count == 0 {
-                            (available_width - row_total_width) / 2.0 + horizontal_space
-                        } else {
-                            horizontal_space
-                        };
-                        ui.horizontal(|ui| {
-                            ui.add_space(edge_space);
-                            for (i, key) in row.iter().enumerate() {
-                                match key {
-                                    Key::Text(text) => self.text_key(ui, text, Some(Vec2::new(button_width * key.width_relative(), button_height))),
-                                    Key::Backspace => self.backspace_key(ui, Some(Vec2::new(button_width * key.width_relative(), button_height))),
-                                    Key::Upper => self.upper_layout_key(ui, Some(Vec2::new(button_width * key.width_relative(), button_height))),
-                                    Key::Space => self.text_key(ui, " ", Some(Vec2::new(button_width * space_relative_width, button_height))),
-                                    Key::Special => self.special_layout_key(ui, Some(Vec2::new(button_width * key.width_relative(), button_height)))
-                                }
-                                if i + 1 < row.len() {
-                                    ui.add_space(horizontal_space);
-                                }
-                            }
-                            ui.add_space(horizontal_space);
-                        });
-                        ui.add_space(vertical_space);
+    /// Queue the events for [`Keyboard::pump_events`], respecting the capacity of the queue.
+    fn push_events(&mut self, events: impl IntoIterator<Item = Event>) {
+        if self.disabled {
+            return;
+        }
+        let events = self.batch_text(events.into_iter().collect());
+        for (event, _) in &events {
+            self.track_typed(event);
+        }
+        if let Some(callback) = self
+            .free_typing
+            .as_mut()
+            .filter(|_| self.free_typing_active)
+        {
+            events.into_iter().for_each(|(event, _)| callback(&event));
+            return;
+        }
+        let capacity = self.event_capacity.unwrap_or(DEFAULT_EVENT_CAPACITY);
+        for (event, next_frame) in events {
+            let event = self.stamp(event);
+            if next_frame {
+                self.frame_breaks.push_back(event.sequence);
+            }
+            let dropped = if self.events.len() < capacity {
+                self.events.push_back(event);
+                None
+            } else {
+                match self.overflow_policy {
+                    OverflowPolicy::DropOldest => {
+                        self.events.push_back(event);
+                        self.events.pop_front()
                     }
-                });
-
-            if let Some(response) = response {
-                self.last_rect = Some(response.response.rect);
-
-                if response.response.contains_pointer() {
-                    // Make sure Egui still thinks that we need the keyboard in the next frame.
-                    self.focus_back_to_input_widget(ctx);
+                    OverflowPolicy::DropNewest => Some(event),
                 }
-            }
+            };
 
-            // Prevent native keyboard from showing up.
-            ctx.output_mut(|output| {
-                output.ime = None;
-            });
-        } else {
-            self.last_rect = None;
+            if let (Some(dropped), Some(callback)) = (dropped, &mut self.on_event_overflow) {
+                callback(&dropped.event);
+            }
         }
     }
 
-    fn clipboard_key(&mut self, ui: &mut Ui, horizontal_space: f32, vertical_space: f32) {
-        if let Some(text) = clipboard::get_text() {
-            ui.horizontal(|ui| {
-                ui.add_space(horizontal_space);
-                if ui.add(button(trim_text(&text, 20), None)).clicked() {
-                    let event = Event::Text(text.to_string());
-                    self.events.push_back(event);
-                    self.focus_back_to_input_widget(ui.ctx());
-                }
-            });
-            ui.add_space(vertical_space);
-        }
+    pub fn layout(mut self, layout: KeyboardLayout) -> Self {
+        self.keyboard_layout = layout;
+        self
     }
 
-    /// Remember which widget had focus before the keyboard was shown.
-    fn remember_input_widget(&mut self, ctx: &Context) {
-        if ctx.wants_keyboard_input() {
-            self.input_widget = ctx.memory(|memory| memory.focused());
-        }
+    /// Frame of the keyboard window, e.g. to give it a border, a shadow or rounded corners. By
+    /// default, the keyboard has no margins and is filled with the `extreme_bg_color` of the
+    /// current style.
+    pub fn frame(mut self, frame: Frame) -> Self {
+        self.frame = Some(frame);
+        self
     }
 
-    /// Focus back to the previously focused widget.
-    fn focus_back_to_input_widget(&mut self, ctx: &Context) {
-        if let Some(focus) = self.input_widget {
-            ctx.memory_mut(|memory| memory.request_focus(focus));
-        }
+    /// Colors, spacing and shapes of the keys, see [`KeyboardStyle`].
+    pub fn style(mut self, style: KeyboardStyle) -> Self {
+        self.set_style(style);
+        self
     }
 
-    fn key(&mut self, ui: &mut Ui, text: &str, event: Event, button_size: Option<Vec2>) {
-        let button = heading_button(text, button_size);
-        let clicked = if let Some(size) = button_size {
-            ui.add_sized(size, button).clicked()
-        } else {
-            ui.add(button).clicked()
-        };
-        if clicked  {
-            self.events.push_back(event);
-            self.focus_back_to_input_widget(ui.ctx());
-        }
+    /// Same as [`Keyboard::style`], for restyling the keyboard at runtime.
+    pub fn set_style(&mut self, style: KeyboardStyle) {
+        self.style = style;
     }
 
-    fn upper_layout_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) {
-        let text = if self.upper {
-            &self.shift_characters[0].to_string()
-        } else {
-            &self.shift_characters[1].to_string()
-        };
-        let button = heading_button(text, button_size);
-        let clicked = if let Some(size) = button_size {
-            ui.add_sized(size, button).clicked()
-        } else {
-            ui.add(button).clicked()
-        };
-        if clicked {
-            self.upper = !self.upper;
-            self.focus_back_to_input_widget(ui.ctx());
-        }
+    /// Opacity of the keyboard background, from `0.0` (fully transparent) to `1.0` (opaque, the
+    /// default). The keys themselves stay opaque, so the content behind the keyboard, like the
+    /// last messages of a chat, remains visible between them.
+    pub fn background_opacity(mut self, opacity: f32) -> Self {
+        self.background_opacity = Some(opacity.clamp(0.0, 1.0));
+        self
     }
 
-    fn special_layout_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) {
-        let text = if self.special {
-            "ABC"
-        } else {
-            "!#1"
-        };
-        let button = heading_button(text, button_size);
-        let clicked = if let Some(size) = button_size {
-            ui.add_sized(size, button).clicked()
-        } else {
-            ui.add(button).clicked()
-        };
-        if clicked {
-            self.special = !self.special;
-            self.focus_back_to_input_widget(ui.ctx());
-        }
+    /// Dim the content beneath the keyboard with a `color` (which should be translucent) while
+    /// the keyboard is shown. Helps keeping the keys readable over a translucent background.
+    pub fn scrim(mut self, color: Color32) -> Self {
+        self.scrim = Some(color);
+        self
     }
 
-    fn backspace_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) {
-        self.key(
-            ui,
-            &self.backspace_character.to_string(),
-            Event::Key {
-                key: egui::Key::Backspace,
-                pressed: true,
-                repeat: false,
-                modifiers: Modifiers::NONE,
-                physical_key: None,
-            },
-            button_size
-        );
+    /// Keep the keys away from unusable edges of the screen. The keyboard background still
+    /// extends to the edges.
+    pub fn safe_area_insets(mut self, insets: SafeAreaInsets) -> Self {
+        self.set_safe_area_insets(insets);
+        self
     }
 
-    fn text_key(&mut self, ui: &mut Ui, text: &str, button_size: Option<Vec2>) {
-        self.key(ui, text, Event::Text(text.to_string()), button_size);
+    /// Same as [`Keyboard::safe_area_insets`], for when the insets change at runtime, e.g. when
+    /// the device is rotated.
+    pub fn set_safe_area_insets(&mut self, insets: SafeAreaInsets) {
+        self.safe_area_insets = insets;
     }
 
-    fn keyboard_input_needed(&mut self, ctx: &Context) -> bool {
-        let needed = if ctx.wants_keyboard_input() {
-            self.needed = 20;
-            true
-        } else {
-            self.needed = self.needed.saturating_sub(1);
-            self.needed > 0
-        };
+    /// Enlarge the keys and their labels, e.g. `1.5` makes them half again as big. When the layout
+    /// does not fit the screen anymore, its rows are split into shorter ones, spread across pages
+    /// if needed. Values below `1.0` are ignored.
+    pub fn accessibility_scale(mut self, scale: f32) -> Self {
+        self.accessibility_scale = Some(scale.max(1.0));
+        self
+    }
 
-        if needed {
-            ctx.request_repaint();
-        }
+    /// Scroll layouts which would need keys narrower than `min_key_width` points to fit the
+    /// width, e.g. a character map, instead of shrinking the keys. Dots below the keys show which
+    /// part of the layout is visible and scroll to the others when tapped.
+    pub fn scroll_wide_layouts(mut self, min_key_width: f32) -> Self {
+        self.scroll_min_key_width = Some(min_key_width.max(1.0));
+        self
+    }
 
-        needed
+    /// Get the keyboard out of the way while the user drag-scrolls content elsewhere in the UI, so
+    /// they can see what they are scrolling to. The keyboard comes back when the scroll ends.
+    pub fn scroll_behavior(mut self, behavior: ScrollBehavior) -> Self {
+        self.scroll_behavior = behavior;
+        self
+    }
+
+    /// What the keys do, e.g. press keys instead of typing text, see [`KeyMode`].
+    pub fn key_mode(mut self, mode: KeyMode) -> Self {
+        self.key_mode = mode;
+        self
+    }
+
+    /// Slide the keyboard in and out over `seconds` instead of showing and hiding it at once. Use
+    /// [`Keyboard::safe_rect`] or [`Keyboard::animated_height`] to move the content along.
+    pub fn show_animation(mut self, seconds: f32) -> Self {
+        self.show_animation = Some(seconds.max(0.0));
+        self
+    }
+
+    /// Wait until the screen and the focused widget stay in place for `frames` frames before
+    /// showing the keyboard, e.g. while a dialog with the focused field is still opening, so the
+    /// keyboard does not show up at a wrong size for a frame. It is shown after 30 frames at the
+    /// latest. Shown right away by default.
+    pub fn settle_frames(mut self, frames: u32) -> Self {
+        self.settle_frames = frames;
+        self
+    }
+
+    /// Guess which key was meant when a press lands between the keys or close to the edge of one,
+    /// based on how far the keys are and how frequently they are used. Keys used often, like `e`,
+    /// become slightly bigger at the expense of their rarely used neighbours. Only applies to the
+    /// presses taken out of the egui input by [`Keyboard::filter_input`].
+    pub fn fat_finger_compensation(mut self, enabled: bool) -> Self {
+        self.fat_finger_compensation = enabled;
+        self
+    }
+
+    /// How long a key has to be held, in seconds, to lock the special layer or to show the
+    /// alternates of the key, like accented letters.
+    pub fn long_press_duration(mut self, seconds: f64) -> Self {
+        self.long_press_duration = Some(seconds.max(0.0));
+        self
+    }
+
+    /// Long-press or right-click the keys to show their alternates or to lock the special layer,
+    /// swipe over the space key to accept the completion and tap with another finger while
+    /// holding shift. On by default. The keys still repeat while held.
+    /// [`Keyboard::flick_gestures`] are separate.
+    pub fn gestures(mut self, enabled: bool) -> Self {
+        self.gestures_disabled = !enabled;
+        self
+    }
+
+    /// Hold the backspace or the space key for `delay` seconds to start repeating it every
+    /// `interval` seconds. Other keys of a custom layout repeat when wrapped in
+    /// [`layouts::LayoutKey::Repeat`].
+    pub fn key_repeat(mut self, delay: f64, interval: f64) -> Self {
+        self.key_repeat = Some((delay.max(0.0), interval.max(0.01)));
+        self
+    }
+
+    /// Digits typed by the number keys, e.g. the native ones of Arabic or Devanagari instead of
+    /// the ASCII ones.
+    pub fn digits(mut self, digits: Digits) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    /// Switch the digits typed by the number keys, e.g. from a settings screen.
+    pub fn set_digits(&mut self, digits: Digits) {
+        self.digits = digits;
+    }
+
+    /// Emojis offered by the emoji layer. The layer is not offered if the dataset is empty, which
+    /// is the default without the `emoji` feature.
+    pub fn emoji_dataset(mut self, dataset: EmojiDataset) -> Self {
+        self.emoji = dataset;
+        self.layer.emoji_tab = 0;
+        self
+    }
+
+    /// Show an additional row with keys moving the caret, extending the selection to the left or
+    /// right, and selecting the word under the caret. Makes text selection feasible on devices
+    /// with a touch screen only.
+    pub fn selection_row(mut self, enabled: bool) -> Self {
+        self.selection_row = enabled;
+        self
+    }
+
+    /// Overlay a translucent heatmap of where the presses landed relative to the key centers.
+    /// Useful for tuning key sizes and detecting a systematic touch offset on particular hardware.
+    pub fn debug_heatmap(mut self, enabled: bool) -> Self {
+        self.heatmap = enabled.then(debug::Heatmap::default);
+        self
+    }
+
+    /// Average offset of the presses from the centers of the keys they hit, normalized to the key
+    /// size, so `(0.5, 0.0)` means that presses land, on average, halfway between the center and
+    /// the right edge. Only available when the debug heatmap is enabled and something was pressed.
+    pub fn press_offset(&self) -> Option<Vec2> {
+        self.heatmap.as_ref()?.mean_offset()
+    }
+
+    /// Draw the computed row widths, button sizes and spacing over the keyboard. Useful when
+    /// a layout renders with uneven gaps.
+    pub fn debug_metrics(mut self, enabled: bool) -> Self {
+        self.debug_metrics = enabled;
+        self
+    }
+
+    /// Forget the presses collected for the debug heatmap.
+    pub fn clear_heatmap(&mut self) {
+        if let Some(heatmap) = &mut self.heatmap {
+            heatmap.clear();
+        }
+    }
+
+    /// Configure the keyboard for the widget with given `id`. The settings are applied when the
+    /// widget gets focus.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # egui::__run_test_ui(|ui| {
+    /// # let mut keyboard = egui_keyboard::Keyboard::default();
+    /// # let mut formula = String::new();
+    /// let response = ui.text_edit_singleline(&mut formula);
+    /// keyboard.widget_config(
+    ///     response.id,
+    ///     egui_keyboard::WidgetConfig {
+    ///         starting_layer: Some(egui_keyboard::layouts::Layer::Special),
+    ///         ..Default::default()
+    ///     },
+    /// );
+    /// # });
+    /// ```
+    pub fn widget_config(&mut self, id: Id, config: WidgetConfig) {
+        self.widget_configs.insert(id, config);
+    }
+
+    /// Tell what is typed into the widget with given `id`, so the keyboard adapts to it when the
+    /// widget has focus, e.g. shows a numeric pad for a number field. Other settings of the
+    /// [`WidgetConfig`] of the widget are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # egui::__run_test_ui(|ui| {
+    /// # let mut keyboard = egui_keyboard::Keyboard::default();
+    /// # let mut age = String::new();
+    /// let response = ui.text_edit_singleline(&mut age);
+    /// keyboard.input_hint(response.id, egui_keyboard::InputHint::Numeric);
+    /// # });
+    /// ```
+    pub fn input_hint(&mut self, id: Id, hint: InputHint) {
+        self.widget_configs.entry(id).or_default().input_hint = hint;
+    }
+
+    /// Offer the `remaining` text completing what is typed into the widget with given `id`, e.g.
+    /// an autocomplete which the application shows as ghost text. If the widget is focused, the
+    /// keyboard shows a key accepting the completion, which can be also accepted by swiping right
+    /// over the space key. Accepting types the `remaining` text into the widget.
+    ///
+    /// The completion is only offered for the current frame, so this needs to be called every
+    /// frame before [`Keyboard::show`].
+    pub fn set_completion(&mut self, id: Id, remaining: impl Into<String>) {
+        self.completion = Some((id, remaining.into()));
+    }
+
+    /// Offer the `text` above the keys, next to the text from the clipboard, e.g. a code from
+    /// a text message received by the application, a username or a recent search. Tapping it
+    /// types it into the focused widget. Several texts can be offered at once.
+    ///
+    /// The text is only offered for the current frame, so this needs to be called every frame
+    /// before [`Keyboard::show`].
+    pub fn offer_suggestion(&mut self, text: impl Into<String>) {
+        self.offered.push(text.into());
+    }
+
+    /// Shortcuts of a hardware keyboard accepting the suggestions, e.g. the completion offered
+    /// by [`Keyboard::set_completion`], on devices where the user types on a physical keyboard
+    /// but still wants the suggestions. The first shortcut accepts the first suggestion and so on.
+    /// Ctrl+1, Ctrl+2 and Ctrl+3 by default.
+    pub fn suggestion_shortcuts(
+        mut self,
+        shortcuts: impl IntoIterator<Item = KeyboardShortcut>,
+    ) -> Self {
+        self.suggestion_shortcuts = Some(shortcuts.into_iter().collect());
+        self
+    }
+
+    /// Widget which receives the key presses. It is the widget which had focus the last time the
+    /// keyboard was shown, except for the keys of the keyboard itself.
+    pub fn target_widget(&self) -> Option<Id> {
+        self.input_widget
+    }
+
+    /// Grey the keyboard out and ignore the presses while `enabled` is false, e.g. while the
+    /// application submits a form. Disabling drops the events waiting for
+    /// [`Keyboard::pump_events`], so they do not end up in whatever widget gets focus next.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.disabled = !enabled;
+        if self.disabled {
+            self.events.clear();
+            self.clear_captured_presses();
+            self.long_pressed = None;
+            self.alternates_popup = None;
+            self.repeating = None;
+            self.shift_held = false;
+            self.stop_peeking();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.disabled
+    }
+
+    /// Bring the keyboard back to its initial state, e.g. when the application switches screens
+    /// or users. Drops the events waiting for [`Keyboard::pump_events`] and the replay in
+    /// progress, switches back to the lower case layer, releases the shift and forgets the
+    /// widgets which had focus. The configuration, like the layout, the style or the
+    /// [`WidgetConfig`]s, is kept.
+    pub fn reset(&mut self) {
+        self.events.clear();
+        self.replay = None;
+        self.shows_since_pump = 0;
+
+        self.layer = LayerState::default();
+        self.widget_layers.clear();
+        self.page = 0;
+        self.shift_held = false;
+        self.typed_while_shift_held = false;
+        self.long_pressed = None;
+        self.alternates_popup = None;
+        self.repeating = None;
+        self.touches.clear();
+        self.stop_peeking();
+        self.space_swipe_origin = None;
+        self.flick_start = None;
+        self.gamepad_cursor = None;
+        self.clear_captured_presses();
+        self.completion = None;
+        self.composition = None;
+        self.typed.clear();
+        self.correction_undo = None;
+        self.typed_language = None;
+
+        self.input_widget = None;
+        self.input_widget_focused = false;
+        self.free_typing_active = false;
+        self.focus_history.clear();
+        self.needed = 0;
+    }
+
+    /// Whether the keyboard may read the clipboard. On the web, the browser asks the user the first
+    /// time the paste key is tapped, and the text is offered only after the access is granted.
+    pub fn clipboard_permission(&self) -> ClipboardPermission {
+        self.clipboard.permission()
+    }
+
+    /// Read the clipboard in the background, asking the user for the access on the web. Needs to
+    /// be called in response to a user gesture, e.g. from a "paste" button of the application.
+    /// The text is offered for pasting once it arrives. Does nothing on other platforms, where
+    /// the clipboard is read whenever the keyboard is shown.
+    pub fn request_clipboard_access(&mut self, ctx: &Context) {
+        self.clipboard.request(ctx);
+    }
+
+    /// Offer the text from the clipboard above the keys and paste it with the Ctrl+V keys of
+    /// custom layouts. On by default with the `clipboard` feature. Turn it off e.g. in a kiosk,
+    /// where the users must not see what others copied.
+    pub fn clipboard(mut self, enabled: bool) -> Self {
+        self.clipboard_disabled = !enabled;
+        self
+    }
+
+    /// How often the text offered for pasting is read from the clipboard while the keyboard is
+    /// shown, e.g. less often where reading it is slow or makes the system notify the user. The
+    /// paste keys read it when they are tapped.
+    pub fn clipboard_polling(mut self, polling: ClipboardPolling) -> Self {
+        self.clipboard_polling = polling;
+        self
+    }
+
+    /// Text from the clipboard, unless the clipboard is turned off.
+    fn clipboard_text(&self) -> Option<String> {
+        self.clipboard.text().filter(|_| !self.clipboard_disabled)
+    }
+
+    /// Text from the clipboard to offer for pasting, as read by the last poll.
+    fn offered_clipboard_text(&mut self) -> Option<String> {
+        if self.clipboard_disabled {
+            return None;
+        }
+        self.clipboard.poll(self.frame_time, self.clipboard_polling)
+    }
+
+    /// Area which is free from the keyboard and the safe area insets. This is useful when you want
+    /// to constrain a window to the area which is not covered by the keyboard.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # egui::__run_test_ctx(|ctx| {
+    /// # let keyboard = egui_keyboard::Keyboard::default();
+    /// egui::Window::new("Hello")
+    ///   .constrain_to(keyboard.safe_rect(ctx))
+    ///   .show(ctx, |ui| {
+    ///      ui.label("it is a window");
+    ///   });
+    /// # });
+    /// ```
+    pub fn safe_rect(&self, ctx: &Context) -> Rect {
+        let screen_rect = ctx.screen_rect();
+        let insets = self.safe_area_insets;
+        let keyboard_height = self.covered_height;
+
+        Rect::from_min_max(
+            screen_rect.min + vec2(insets.left, 0.),
+            screen_rect.max - vec2(insets.right, keyboard_height.max(insets.bottom)),
+        )
+    }
+
+    /// How much of the screen height the keyboard covers, following the show animation, so the
+    /// content of the application can slide along with the keyboard. Zero when the keyboard is
+    /// hidden.
+    pub fn animated_height(&self) -> f32 {
+        self.covered_height
+    }
+
+    /// Shows the virtual keyboard if needed, placed according to [`Keyboard::placement`].
+    ///
+    /// Taps landing on the keyboard in the same frame, e.g. at a low frame rate, are handled
+    /// one per frame in the order they came, so none of them is lost:
+    ///
+    /// ```
+    /// use egui::{pos2, vec2, Event, Modifiers, PointerButton, RawInput, Rect};
+    ///
+    /// let ctx = egui::Context::default();
+    /// let mut keyboard = egui_keyboard::Keyboard::default();
+    /// let mut text = String::new();
+    /// let mut frame = |events: Vec<Event>| {
+    ///     let screen_rect = Some(Rect::from_min_size(pos2(0.0, 0.0), vec2(400.0, 800.0)));
+    ///     let input = RawInput { screen_rect, events, ..Default::default() };
+    ///     let _ = ctx.run(input, |ctx| {
+    ///         keyboard.pump_events(ctx);
+    ///         egui::CentralPanel::default().show(ctx, |ui| {
+    ///             ui.text_edit_singleline(&mut text).request_focus();
+    ///         });
+    ///         keyboard.show(ctx);
+    ///     });
+    /// };
+    /// for _ in 0..3 {
+    ///     frame(Vec::new());
+    /// }
+    ///
+    /// // Two taps on the space key in the middle of the bottom row, within a single frame.
+    /// let pos = pos2(200.0, 775.0);
+    /// let tap = |pressed| Event::PointerButton {
+    ///     pos,
+    ///     button: PointerButton::Primary,
+    ///     pressed,
+    ///     modifiers: Modifiers::NONE,
+    /// };
+    /// frame(vec![Event::PointerMoved(pos), tap(true), tap(false), tap(true), tap(false)]);
+    /// for _ in 0..3 {
+    ///     frame(Vec::new());
+    /// }
+    /// assert_eq!(text, "  ");
+    /// ```
+    pub fn show(&mut self, ctx: &Context) {
+        let needed = self.begin_show(ctx);
+
+        let mut shown = Vec::new();
+        if self.visibility > 0. {
+            let keys = self.keys_to_show(ctx);
+
+            // How much the keyboard is out of the way because of a scroll, from 0 to 1.
+            let away = ctx.animate_bool_with_time(
+                Id::new("keyboard_scroll_away"),
+                self.scroll_behavior != ScrollBehavior::Stay && self.scrolling_elsewhere(ctx),
+                0.2,
+            );
+            let mut frame = self.keyboard_frame(ctx);
+            if self.scroll_behavior == ScrollBehavior::Dim {
+                frame = frame.multiply_with_opacity(1. - 0.9 * away);
+            }
+
+            shown = match self.placement {
+                KeyboardPlacement::Overlay => self.show_overlay(ctx, keys, frame, away, needed),
+                KeyboardPlacement::Docked => self.show_docked(ctx, keys, frame, away),
+                KeyboardPlacement::Floating => self.show_floating(ctx, keys, frame, away, needed),
+                KeyboardPlacement::Split => self.show_split(ctx, keys, frame, away, needed),
+                KeyboardPlacement::Follow => self.show_following(ctx, keys, frame, away, needed),
+            };
+            shown.extend(self.show_numpad_companion(ctx, frame, away, needed));
+            if let Some(scrim) = self.scrim {
+                Self::show_scrim(ctx, scrim, &shown);
+            }
+        }
+        self.end_show(ctx, needed, &shown);
+    }
+
+    /// Dim everything beneath the `shown` keyboard windows with the `scrim` color, on a layer of
+    /// its own which the windows are kept directly above.
+    fn show_scrim(ctx: &Context, scrim: Color32, shown: &[Response]) {
+        let mut rect = ctx.screen_rect();
+        for response in shown {
+            if response.layer_id.order != Order::Foreground {
+                // The docked panel takes its own part of the screen, nothing is beneath it.
+                rect.max.y = rect.max.y.min(response.rect.top());
+            }
+        }
+        let layer_id = Area::new(Id::new("keyboard_scrim"))
+            .order(Order::Foreground)
+            .fixed_pos(rect.min)
+            .interactable(false)
+            .show(ctx, |ui| ui.painter().rect_filled(rect, 0.0, scrim))
+            .response
+            .layer_id;
+        for response in shown {
+            if response.layer_id.order == Order::Foreground {
+                ctx.set_sublayer(layer_id, response.layer_id);
+            }
+        }
+    }
+
+    /// Update the state of the keyboard at the start of the frame, before it is shown. Returns
+    /// whether the keyboard is needed.
+    fn begin_show(&mut self, ctx: &Context) -> bool {
+        self.frame_time = ctx.input(|input| input.time);
+        self.shows_since_pump += 1;
+        debug_assert!(
+            self.shows_since_pump <= 1 || self.events.is_empty(),
+            "key presses are not delivered, Keyboard::pump_events needs to be called every frame"
+        );
+
+        self.remember_input_widget(ctx);
+        self.remember_layout(ctx);
+        self.capture_pressed_together(ctx);
+        self.start_captured_press(ctx);
+        self.update_background_lookups(ctx);
+        self.handle_suggestion_shortcuts(ctx);
+
+        let needed = self.keyboard_input_needed(ctx);
+        if !needed && self.visibility > 0. {
+            // Hidden as nothing is typed into anymore.
+            self.commit_field();
+        }
+        let shown = self.settle(ctx, needed);
+        self.visibility = match self.show_animation {
+            // The animation needs to be known to egui even when hidden, otherwise it would start
+            // at its target, skipping the slide in.
+            Some(time) => ctx.animate_bool_with_time(Id::new("keyboard_visibility"), shown, time),
+            None if shown => 1.,
+            None => 0.,
+        };
+        needed
+    }
+
+    /// Rows of keys to show in this frame.
+    fn keys_to_show(&mut self, ctx: &Context) -> Vec<Row> {
+        if self.last_rects.is_empty() && !self.clipboard_disabled {
+            // Keyboard is just being shown, the clipboard might have changed in the meantime.
+            self.clipboard.refresh(ctx);
+            self.clipboard.forget();
+        }
+        if !self.disabled && !self.gestures_disabled {
+            self.handle_secondary_touches(ctx);
+        }
+        self.key_rects.clear();
+
+        let mut keys = self.rows_to_show();
+        self.update_layer_transition(ctx);
+        self.update_flicks(&keys);
+        if self.selection_row {
+            keys.insert(0, SelectionKey::ROW.to_vec().into());
+        }
+        keys
+    }
+
+    /// Rows of the layout or the emojis without the hidden keys, placed for the handedness.
+    fn rows_to_show(&self) -> Vec<Row> {
+        let mut keys = if self.layer.emoji {
+            self.emoji_rows()
+        } else {
+            self.layout_rows()
+        };
+        self.remove_hidden_keys(&mut keys);
+        self.mirror_for_handedness(&mut keys);
+        keys
+    }
+
+    /// Frame around the keys, filled according to the style unless set explicitly.
+    fn keyboard_frame(&self, ctx: &Context) -> Frame {
+        let frame = self.frame.unwrap_or_else(|| {
+            Frame::NONE.fill(
+                self.layer_style()
+                    .background
+                    .or(self.style.background)
+                    .unwrap_or(ctx.style().visuals.extreme_bg_color),
+            )
+        });
+        match self.background_opacity {
+            Some(opacity) => frame.multiply_with_opacity(opacity),
+            None => frame,
+        }
+    }
+
+    /// Remember where the keyboard was `shown` and show the popups over it, or forget the state
+    /// of the presses if it is hidden.
+    fn end_show(&mut self, ctx: &Context, needed: bool, shown: &[Response]) {
+        if self.visibility > 0. {
+            self.last_rects = shown
+                .iter()
+                .map(|response| (response.layer_id, response.rect))
+                .collect();
+            if needed && shown.iter().any(|response| response.contains_pointer()) {
+                // Make sure Egui still thinks that we need the keyboard in the next frame.
+                self.focus_back_to_input_widget(ctx);
+            }
+
+            self.show_alternates_popup(ctx);
+            self.show_composition(ctx);
+            self.snap_gamepad_cursor();
+
+            // Prevent native keyboard from showing up.
+            ctx.output_mut(|output| {
+                output.ime = None;
+            });
+        } else {
+            self.stop_peeking();
+            self.last_rects.clear();
+            self.action_bar_rect = None;
+            self.gamepad_cursor = None;
+            self.covered_height = 0.;
+            self.clear_captured_presses();
+            self.alternates_popup = None;
+        }
+        self.finish_captured_press(ctx);
+        self.show_calibration(ctx);
+
+        // Completion, misspelled word, offered texts, composition and the rects to avoid and follow
+        // need to be given again in the next frame.
+        self.completion = None;
+        self.misspelled = None;
+        self.offered.clear();
+        self.composition = None;
+        self.avoided.clear();
+        self.followed = None;
+    }
+
+    /// Bounding rect of the keyboard shown in the last frame, without the numpad next to it.
+    pub(crate) fn last_rect(&self) -> Option<Rect> {
+        self.last_rects
+            .iter()
+            .filter(|(layer, _)| *layer != numpad::numpad_layer())
+            .map(|(_, rect)| *rect)
+            .reduce(|a, b| a.union(b))
+    }
+
+    /// Whether the `pos` is on the action bar shown in the last frame, whose widgets need the
+    /// presses to reach egui.
+    pub(crate) fn on_action_bar(&self, pos: Pos2) -> bool {
+        self.action_bar_rect.is_some_and(|rect| rect.contains(pos))
+    }
+
+    /// Whether the `pos` is on the keyboard shown in the last frame. Only the keys themselves
+    /// count for the floating keyboard, the rest of the window is for dragging it.
+    pub(crate) fn covers(&self, pos: Pos2) -> bool {
+        match self.placement {
+            KeyboardPlacement::Floating => {
+                self.key_rects.iter().any(|(rect, _)| rect.contains(pos))
+            }
+            _ => self.last_rects.iter().any(|(_, rect)| rect.contains(pos)),
+        }
+    }
+
+    /// Show the `keys` in the `ui`, with the rows taking the given `height` in total. The
+    /// suggestions and the clipboard chips are shown above them and the action bar below them
+    /// if `extras` is set, otherwise the action bar only leaves its row empty.
+    fn keys_ui(&mut self, ui: &mut Ui, keys: Vec<Row>, height: f32, away: f32, extras: bool) {
+        // We do not want any spacing between the keys.
+        ui.style_mut().spacing.item_spacing = Vec2::ZERO;
+        if self.disabled {
+            ui.disable();
+        }
+
+        if self.scroll_behavior == ScrollBehavior::Dim {
+            ui.multiply_opacity(1. - 0.9 * away);
+        }
+
+        let insets = self.safe_area_insets;
+        let available_width = ui.available_width() - insets.left - insets.right;
+        let pixels_per_point = ui.ctx().pixels_per_point();
+        let screen_width = ui.ctx().screen_rect().width();
+        let metrics = self.keys_metrics(
+            pixels_per_point,
+            keys,
+            available_width,
+            height,
+            screen_width,
+        );
+        if let Some(font) = ui.style_mut().text_styles.get_mut(&self.style.text_style()) {
+            font.size = self.style.font_size.unwrap_or(font.size) * metrics.scale;
+        }
+        self.page = metrics.page;
+        let pages = metrics.pages;
+        let keys = &pages[self.page];
+        let (widest_row, rows_width, scrolled) =
+            (metrics.widest_row, metrics.rows_width, metrics.scrolled);
+        let (button_width, button_height) = (metrics.button_size.x, metrics.button_size.y);
+        let (horizontal_space, vertical_space) = (metrics.space.x, metrics.space.y);
+
+        if self.debug_metrics {
+            debug::paint_metrics(
+                ui.painter(),
+                ui.max_rect(),
+                &format!(
+                    "button {button_width:.1}x{button_height:.1}, gaps {horizontal_space:.1}x{vertical_space:.1}, widest row {widest_row:.2} buttons"
+                ),
+            );
+        }
+
+        ui.add_space(vertical_space);
+        if extras {
+            let edge = match self.handedness {
+                Handedness::Right => insets.left,
+                Handedness::Left => insets.right,
+            };
+            self.chips_row(
+                ui,
+                edge + horizontal_space,
+                horizontal_space,
+                vertical_space,
+            );
+        }
+
+        let button_size = vec2(button_width, button_height);
+        let space = vec2(horizontal_space, vertical_space);
+        let (opacity, offset) = self.layer_transition_effect(ui.available_width());
+        ui.with_visual_transform(TSTransform::from_translation(offset), |ui| {
+            ui.multiply_opacity(opacity);
+            if scrolled {
+                let mut scroll_area = ScrollArea::horizontal()
+                    .id_salt("keyboard_rows")
+                    .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden);
+                if let Some(offset) = self.scroll_to.take() {
+                    scroll_area = scroll_area.horizontal_scroll_offset(offset);
+                }
+                let output = scroll_area.show(ui, |ui| {
+                    self.rows_ui(ui, keys, pages.len(), button_size, space, rows_width)
+                });
+                // The inner rect of the output is only as tall as the space the scroll area got.
+                let viewport = Rect::from_min_max(
+                    output.inner_rect.min,
+                    pos2(output.inner_rect.right(), ui.cursor().min.y),
+                );
+                self.scroll_indicator(
+                    ui,
+                    viewport,
+                    output.content_size.x,
+                    output.state.offset.x,
+                    vertical_space,
+                );
+            } else {
+                self.rows_ui(ui, keys, pages.len(), button_size, space, rows_width);
+            }
+        });
+
+        if !extras && self.action_bar.is_some() {
+            ui.add_space(button_height + vertical_space);
+        } else if let Some(bar) = &mut self.action_bar {
+            let size = vec2(available_width - 2.0 * horizontal_space, button_height);
+            let min = ui.cursor().min + vec2(insets.left + horizontal_space, 0.0);
+            let rect = Rect::from_min_size(min, size);
+            ui.scope_builder(
+                UiBuilder::new()
+                    .max_rect(rect)
+                    .layout(Layout::left_to_right(Align::Center)),
+                |ui| {
+                    ui.spacing_mut().item_spacing.x = horizontal_space;
+                    bar(ui, vec2(button_width, button_height));
+                },
+            );
+            ui.advance_cursor_after_rect(rect);
+            ui.add_space(vertical_space);
+            self.action_bar_rect = Some(rect);
+        }
+
+        ui.add_space(insets.bottom);
+    }
+
+    /// Dots below the rows scrolled in the `viewport`, one for each viewport-wide part of the
+    /// `content_width`, highlighting the one at the scroll `offset`. Tapping a dot scrolls to its
+    /// part.
+    fn scroll_indicator(
+        &mut self,
+        ui: &Ui,
+        viewport: Rect,
+        content_width: f32,
+        offset: f32,
+        gap: f32,
+    ) {
+        if viewport.width() <= 0.0 {
+            // Nothing is visible yet, e.g. in the first frame or in a collapsed panel.
+            return;
+        }
+        let parts = (content_width / viewport.width()).ceil().max(1.0) as usize;
+        let max_offset = (content_width - viewport.width()).max(0.0);
+        let current = if max_offset > 0.0 {
+            (offset / max_offset * (parts - 1) as f32).round() as usize
+        } else {
+            0
+        };
+        let radius = (gap / 4.0).max(2.0);
+        // Dots are far apart, so they are easier to tap.
+        let step = 3.0 * gap;
+        let first = viewport.center().x - step * (parts - 1) as f32 / 2.0;
+        // The dots sit in the gap below the last row.
+        let y = viewport.bottom() - gap / 2.0;
+        for part in 0..parts {
+            let center = pos2(first + step * part as f32, y);
+            let response = ui.interact(
+                Rect::from_center_size(center, vec2(step, gap)),
+                ui.id().with(("keyboard_scroll_part", part)),
+                Sense::CLICK,
+            );
+            if self.interaction(ui, &response).clicked {
+                self.scroll_to = Some(max_offset * part as f32 / (parts - 1).max(1) as f32);
+            }
+            let color = match part == current {
+                true => ui.visuals().strong_text_color(),
+                false => ui.visuals().weak_text_color(),
+            };
+            ui.painter().circle_filled(center, radius, color);
+        }
+    }
+
+    /// Show the rows of `keys` taking the given `width`, with the keys of `button_size` and the
+    /// `space` between them.
+    fn rows_ui(
+        &mut self,
+        ui: &mut Ui,
+        keys: &[Row],
+        pages_count: usize,
+        button_size: Vec2,
+        space: Vec2,
+        width: f32,
+    ) {
+        let (button_width, button_height) = (button_size.x, button_size.y);
+        let (horizontal_space, vertical_space) = (space.x, space.y);
+        let insets = self.safe_area_insets;
+
+        // Horizontal ranges taken by the tall keys from the rows above, with how many
+        // more rows they span.
+        let mut spans: Vec<(Rangef, usize)> = Vec::new();
+
+        for row in keys.iter() {
+            if row.keys.is_empty() {
+                continue;
+            }
+            let spans_width = spans
+                .iter()
+                .map(|(span, _)| span.span() + horizontal_space)
+                .sum::<f32>();
+            let row_metrics =
+                geometry::RowMetrics::new(row, button_width, horizontal_space, width, spans_width);
+            let edge_space = row_metrics.edge_space;
+            let row_response = ui.horizontal(|ui| {
+                ui.add_space(insets.left + edge_space);
+                for (i, key) in row.keys.iter().enumerate() {
+                    let button_size =
+                        Vec2::new(button_width * row_metrics.key_width(key), button_height);
+                    skip_spans(ui, &spans, button_size.x, horizontal_space);
+                    let response = match key {
+                        Key::Tall { rows, .. } => {
+                            let rows = (*rows).max(1);
+                            let tall_size = vec2(
+                                button_size.x,
+                                rows as f32 * button_height + (rows - 1) as f32 * vertical_space,
+                            );
+                            let rect = Rect::from_min_size(ui.cursor().min, tall_size);
+                            // The key must not make its own row taller.
+                            let mut tall_ui = ui.new_child(UiBuilder::new().max_rect(rect));
+                            let response = self.any_key(&mut tall_ui, key, pages_count, tall_size);
+                            ui.advance_cursor_after_rect(Rect::from_min_size(
+                                rect.min,
+                                button_size,
+                            ));
+                            spans.push((response.rect.x_range(), rows));
+                            response
+                        }
+                        _ => self.any_key(ui, key, pages_count, button_size),
+                    };
+                    if !matches!(key.base(), Key::Gap(_)) {
+                        self.key_rects.push((response.rect, key.clone()));
+                    }
+                    if i + 1 < row.keys.len() {
+                        ui.add_space(horizontal_space);
+                    }
+                }
+                ui.add_space(horizontal_space);
+            });
+            if self.debug_metrics {
+                debug::paint_metrics(
+                    ui.painter(),
+                    row_response.response.rect,
+                    &format!(
+                        "computed {:.1}, rendered {:.1}, edge {edge_space:.1}, space key {:.1}",
+                        row_metrics.total_width,
+                        row_response.response.rect.width() - insets.left - edge_space
+                            + horizontal_space,
+                        button_width * row_metrics.space_width,
+                    ),
+                );
+            }
+            ui.add_space(vertical_space);
+
+            for (_, rows) in &mut spans {
+                *rows -= 1;
+            }
+            spans.retain(|(_, rows)| *rows > 0);
+        }
+    }
+
+    /// Rows of the layout, with the key switching to the emoji layer if there are any emojis and
+    /// the globe key if there are several layouts.
+    fn layout_rows(&self) -> Vec<Row> {
+        let mut rows = self
+            .keyboard_layout
+            .get_keys(self.layer.upper || self.shift_held, self.layer.special);
+        self.tailor_bottom_row(&mut rows);
+        match self.focused_input_hint() {
+            InputHint::Numeric if self.calculator => return layouts::calculator(),
+            InputHint::Numeric => return layouts::numeric(),
+            InputHint::Email => layouts::put_around_space(&mut rows, "@", "."),
+            InputHint::Url => layouts::put_around_space(&mut rows, "/", "."),
+            InputHint::Text | InputHint::Password => {}
+        }
+        let has_emoji_key = rows
+            .iter()
+            .flatten()
+            .any(|key| matches!(key.base(), Key::EmojiLayer));
+        let has_globe_key = rows
+            .iter()
+            .flatten()
+            .any(|key| matches!(key.base(), Key::Globe));
+        let mut extra_keys = Vec::new();
+        if self.bottom_row.emoji && !self.emoji.emojis.is_empty() && !has_emoji_key {
+            extra_keys.push(Key::EmojiLayer);
+        }
+        if self.bottom_row.globe && self.layouts.len() > 1 && !has_globe_key {
+            extra_keys.push(Key::Globe);
+        }
+        if self.password_peek.is_some() && self.focused_input_hint() == InputHint::Password {
+            extra_keys.push(Key::Peek);
+        }
+        if self.bottom_row.dictation && self.on_dictation.is_some() {
+            extra_keys.push(Key::Dictation);
+        }
+        if let Some(row) = rows.last_mut() {
+            let position = row
+                .keys
+                .iter()
+                .position(|key| matches!(key.base(), Key::Special))
+                .map_or(0, |special| special + 1);
+            row.keys.splice(position..position, extra_keys);
+            if self.bottom_row.dismiss {
+                row.keys.push(Key::Dismiss);
+            }
+        }
+        rows
+    }
+
+    /// What is typed into the widget receiving the key presses.
+    fn focused_input_hint(&self) -> InputHint {
+        self.input_widget
+            .and_then(|id| self.widget_configs.get(&id))
+            .map(|config| config.input_hint)
+            .unwrap_or_default()
+    }
+
+    /// Rows of the emoji layer: the tabs, the emojis of the shown tab and the bottom row.
+    fn emoji_rows(&self) -> Vec<Row> {
+        let tabs = self.emoji.tabs();
+        let tab = tabs
+            .get(self.layer.emoji_tab)
+            .or(tabs.first())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut rows = vec![Row::from(
+            (0..tabs.len()).map(Key::EmojiTab).collect::<Vec<_>>(),
+        )];
+        let emojis: Vec<Key> = tab.map(Key::Emoji).collect();
+        let emoji_rows = EMOJIS_PER_TAB.div_ceil(EMOJI_COLUMNS);
+        for i in 0..emoji_rows {
+            // Tabs with fewer emojis keep the keyboard as tall as the full ones.
+            let row = emojis
+                .chunks(EMOJI_COLUMNS)
+                .nth(i)
+                .map_or_else(|| vec![Key::Gap(0.0)], <[Key]>::to_vec);
+            rows.push(Row::from(row).align(RowAlign::Left));
+        }
+        rows.push(vec![Key::EmojiLayer, Key::Space, Key::Backspace].into());
+        rows
+    }
+
+    /// Row with the result of the typed calculation, the completion offered by the application,
+    /// the corrections, the suggested words, the texts offered by the application and the text
+    /// from the clipboard, starting `edge` away from the left edge, or the right one for
+    /// left-handed users.
+    fn chips_row(&mut self, ui: &mut Ui, edge: f32, horizontal_space: f32, vertical_space: f32) {
+        let calculation = self.calculation();
+        let completion = self.completion().map(str::to_owned);
+        let corrections = self.corrections();
+        let words = self.word_suggestions();
+        let mut offered = self.offered.clone();
+        offered.extend(self.autofill_suggestions());
+        let password = self.focused_input_hint() == InputHint::Password;
+        let clipboard = self.offered_clipboard_text().filter(|_| !password);
+        // Reading the clipboard on the web needs the user to allow it first.
+        let ask_for_clipboard = clipboard.is_none()
+            && !password
+            && !self.clipboard_disabled
+            && self.clipboard.permission() == ClipboardPermission::Unknown;
+        if calculation.is_none()
+            && completion.is_none()
+            && corrections.is_empty()
+            && words.is_empty()
+            && offered.is_empty()
+            && clipboard.is_none()
+            && !ask_for_clipboard
+        {
+            return;
+        }
+
+        let layout = match self.handedness {
+            Handedness::Right => Layout::left_to_right(Align::Center),
+            Handedness::Left => Layout::right_to_left(Align::Center),
+        };
+        let size = vec2(ui.available_width(), ui.spacing().interact_size.y);
+        ui.allocate_ui_with_layout(size, layout, |ui| {
+            ui.add_space(edge);
+            if let Some(result) = calculation {
+                let label = format!("= {}", trim_text(&result, 20));
+                let response = self.chip(ui, SuggestionKind::Calculation, &result, label);
+                if self.interaction(ui, &response).clicked {
+                    self.accept_calculation(ui.ctx());
+                }
+                ui.add_space(horizontal_space);
+            }
+            if let Some(completion) = completion {
+                let label = format!("⏩ {}", trim_text(&completion, 20));
+                let response = self.chip(ui, SuggestionKind::Completion, &completion, label);
+                if self.interaction(ui, &response).clicked {
+                    self.accept_completion(ui.ctx());
+                }
+                ui.add_space(horizontal_space);
+            }
+            for correction in corrections {
+                let label = format!("✔ {}", trim_text(&correction, 20));
+                let response = self.chip(ui, SuggestionKind::Correction, &correction, label);
+                if self.interaction(ui, &response).clicked {
+                    self.accept_correction(ui.ctx(), &correction);
+                }
+                ui.add_space(horizontal_space);
+            }
+            for word in words {
+                let response = self.chip(ui, SuggestionKind::Word, &word, trim_text(&word, 20));
+                if self.interaction(ui, &response).clicked {
+                    self.accept_suggestion(ui.ctx(), &word);
+                }
+                ui.add_space(horizontal_space);
+            }
+            for text in offered {
+                let response = self.chip(ui, SuggestionKind::Offered, &text, trim_text(&text, 20));
+                if self.interaction(ui, &response).clicked {
+                    self.push_events([Event::Text(text)]);
+                    self.focus_back_to_input_widget(ui.ctx());
+                }
+                ui.add_space(horizontal_space);
+            }
+            if let Some(text) = clipboard {
+                let response =
+                    self.chip(ui, SuggestionKind::Clipboard, &text, trim_text(&text, 20));
+                if self.interaction(ui, &response).clicked {
+                    self.push_events([Event::Text(text.to_string())]);
+                    self.focus_back_to_input_widget(ui.ctx());
+                }
+            } else if ask_for_clipboard {
+                let response = ui.add(button("📋", self.style.min_key_size));
+                if self.interaction(ui, &response).clicked {
+                    self.clipboard.request(ui.ctx());
+                    self.focus_back_to_input_widget(ui.ctx());
+                }
+            }
+        });
+        ui.add_space(vertical_space);
+    }
+
+    /// Completion offered for the focused widget.
+    fn completion(&self) -> Option<&str> {
+        self.completion
+            .as_ref()
+            .filter(|(id, _)| Some(*id) == self.input_widget)
+            .map(|(_, text)| text.as_str())
+    }
+
+    /// Type the rest of the offered completion.
+    fn accept_completion(&mut self, ctx: &Context) {
+        if let Some(text) = self.completion().map(str::to_owned) {
+            self.push_events([Event::Text(text)]);
+            self.completion = None;
+            self.focus_back_to_input_widget(ctx);
+        }
+    }
+
+    /// Accept the suggestion whose shortcut was pressed on a hardware keyboard.
+    fn handle_suggestion_shortcuts(&mut self, ctx: &Context) {
+        let completion = self.completion().is_some();
+        let corrections = self.corrections();
+        let words = self.word_suggestions();
+        let count = usize::from(completion) + corrections.len() + words.len();
+
+        let shortcuts = self
+            .suggestion_shortcuts
+            .as_deref()
+            .unwrap_or(&DEFAULT_SUGGESTION_SHORTCUTS);
+        // The completion comes first, followed by the corrections and the words, like in the
+        // suggestion bar.
+        let pressed = shortcuts
+            .iter()
+            .take(count)
+            .position(|shortcut| ctx.input_mut(|input| input.consume_shortcut(shortcut)));
+        match pressed.map(|i| i.checked_sub(usize::from(completion))) {
+            Some(None) => self.accept_completion(ctx),
+            Some(Some(i)) if i < corrections.len() => self.accept_correction(ctx, &corrections[i]),
+            Some(Some(i)) => self.accept_suggestion(ctx, &words[i - corrections.len()]),
+            None => {}
+        }
+    }
+
+    /// Swiping right over the space key accepts the offered completion.
+    fn space_swipe(&mut self, ui: &Ui, response: &Response) {
+        if self.gestures_disabled {
+            return;
+        }
+        let interaction = self.interaction(ui, response);
+        if interaction.down {
+            if self.space_swipe_origin.is_none() {
+                self.space_swipe_origin = interaction.origin;
+            }
+            return;
+        }
+
+        // Egui stops reporting the press on the key when the pointer moves away, so wait for
+        // the release.
+        if ui.input(|input| input.pointer.any_down()) {
+            return;
+        }
+
+        if let Some(origin) = self.space_swipe_origin.take() {
+            let end = interaction.pos;
+            if end.is_some_and(|end| end.x - origin.x > response.rect.width() / 2.) {
+                self.accept_completion(ui.ctx());
+            }
+        }
+    }
+
+    /// Whether the user drags something which started outside of the keyboard, like a scroll
+    /// area.
+    fn scrolling_elsewhere(&self, ctx: &Context) -> bool {
+        ctx.input(|input| {
+            input.pointer.is_decidedly_dragging()
+                && input
+                    .pointer
+                    .press_origin()
+                    .is_some_and(|origin| !self.covers(origin))
+        })
+    }
+
+    /// Remember which widget had focus before the keyboard was shown.
+    fn remember_input_widget(&mut self, ctx: &Context) {
+        match ctx.memory(|memory| memory.focused()) {
+            // Keys can not get focus, but the keyboard window can when tabbing through the
+            // widgets. It is not where the text goes, so tabbing goes on from nothing focused.
+            Some(focused) if self.is_own_widget(ctx, focused) => {
+                ctx.memory_mut(|memory| memory.surrender_focus(focused));
+            }
+            // Not a widget the keyboard types into, see `Keyboard::scope`.
+            Some(focused) if !self.in_scope(ctx, focused) => {}
+            Some(focused) if Some(focused) != self.input_widget => {
+                self.switch_input_widget(focused)
+            }
+            Some(_) => {}
+            None if self.input_widget_focused => self.restore_focus_after_popup(ctx),
+            None => {}
+        }
+
+        let focused = ctx.memory(|memory| memory.focused());
+        self.input_widget_focused = focused.is_some() && focused == self.input_widget;
+    }
+
+    fn switch_input_widget(&mut self, focused: Id) {
+        if let Some(previous) = self.input_widget {
+            self.widget_layers.insert(previous, self.layer);
+            self.focus_history
+                .retain(|id| *id != previous && *id != focused);
+            self.focus_history.push(previous);
+            if self.focus_history.len() > FOCUS_HISTORY_LENGTH {
+                self.focus_history.remove(0);
+            }
+        }
+        self.stop_peeking();
+        self.commit_field();
+        self.input_widget = Some(focused);
+        self.layer = self.initial_layer();
+    }
+
+    /// When the input widget disappears while focused, e.g. because it was in a popup which got
+    /// closed, give the focus back to the widget which had it before.
+    fn restore_focus_after_popup(&mut self, ctx: &Context) {
+        let Some(current) = self
+            .input_widget
+            .filter(|_| !self.focus_management_disabled)
+        else {
+            return;
+        };
+        if ctx.read_response(current).is_some() {
+            // Still there, so the focus was given up deliberately.
+            return;
+        }
+
+        while let Some(previous) = self.focus_history.pop() {
+            if ctx.read_response(previous).is_some() {
+                self.widget_layers.insert(current, self.layer);
+                self.commit_field();
+                self.input_widget = Some(previous);
+                self.layer = self.initial_layer();
+                ctx.memory_mut(|memory| memory.request_focus(previous));
+                return;
+            }
+        }
+    }
+
+    /// Whether the widget is a part of the keyboard window.
+    fn is_own_widget(&self, ctx: &Context, id: Id) -> bool {
+        // Docked and embedded keyboards share their layer with other widgets.
+        ctx.read_response(id).is_some_and(|response| {
+            self.last_rects.iter().any(|(layer, rect)| {
+                *layer == response.layer_id && rect.contains_rect(response.rect)
+            })
+        })
+    }
+
+    /// Layer state for the newly focused widget. It is either restored from the last time the
+    /// widget had focus, or chosen according to its settings.
+    fn initial_layer(&self) -> LayerState {
+        let Some(id) = self.input_widget else {
+            return LayerState::default();
+        };
+
+        if let Some(layer) = self.widget_layers.get(&id) {
+            return *layer;
+        }
+
+        self.widget_configs
+            .get(&id)
+            .and_then(|config| config.starting_layer)
+            .map(LayerState::new)
+            .unwrap_or_default()
+    }
+
+    /// Focus back to the previously focused widget.
+    fn focus_back_to_input_widget(&mut self, ctx: &Context) {
+        // Focus would be taken from whatever the free typing goes to.
+        if self.free_typing_active || self.focus_management_disabled {
+            return;
+        }
+        if let Some(focus) = self.input_widget {
+            ctx.memory_mut(|memory| memory.request_focus(focus));
+        }
+    }
+
+    /// Add the button of a key. Keys of active modifiers, like a latched shift, are `selected`.
+    fn add_key_button(
+        &mut self,
+        ui: &mut Ui,
+        text: &str,
+        selected: bool,
+        category: KeyCategory,
+        button_size: Option<Vec2>,
+    ) -> Response {
+        let shape = self.style.key_shapes.get(&category).copied();
+
+        // Keys follow the visuals of egui widgets, with the colors of the style on top.
+        let (widgets, selection) = (ui.visuals().widgets.clone(), ui.visuals().selection);
+        let layer_style = self.layer_style();
+        let colors = layer_style.keys.or(self.style.key_colors(category));
+        if let Some(accent) = layer_style.accent {
+            ui.visuals_mut().selection.bg_fill = accent;
+        }
+        let visuals = &mut ui.visuals_mut().widgets;
+        for (state, fill) in [
+            (&mut visuals.inactive, colors.fill),
+            (&mut visuals.hovered, colors.fill_hovered),
+            (&mut visuals.active, colors.fill_pressed),
+        ] {
+            if let Some(fill) = fill {
+                state.weak_bg_fill = fill;
+            }
+            if let Some(text) = colors.text {
+                state.fg_stroke.color = text;
+            }
+        }
+
+        let response = match (shape, button_size) {
+            (Some(shape), Some(size)) => self.add_shaped_key(ui, text, selected, shape, size),
+            (_, size) => {
+                let button = self.key_button_widget(text, size).selected(selected);
+                if let Some(size) = size {
+                    ui.add_sized(size, button)
+                } else {
+                    ui.add(button)
+                }
+            }
+        };
+        ui.visuals_mut().widgets = widgets;
+        ui.visuals_mut().selection = selection;
+
+        let interaction = self.interaction(ui, &response);
+        if interaction.captured && interaction.down {
+            // Egui does not know about the press, so it does not draw the key as pressed.
+            let visuals = ui.visuals().widgets.active;
+            let shape = shape.unwrap_or(KeyShape::Rounded {
+                corner_radius: visuals.corner_radius.average(),
+            });
+            shape.paint(
+                ui.painter(),
+                response.rect,
+                Color32::TRANSPARENT,
+                visuals.fg_stroke,
+            );
+        }
+        if self.is_gamepad_highlighted(response.rect) {
+            let visuals = ui.visuals();
+            let shape = shape.unwrap_or(KeyShape::Rounded {
+                corner_radius: visuals.widgets.active.corner_radius.average(),
+            });
+            let stroke = Stroke::new(gamepad::HIGHLIGHT_WIDTH, visuals.selection.stroke.color);
+            shape.paint(ui.painter(), response.rect, Color32::TRANSPARENT, stroke);
+        }
+
+        if let Some(heatmap) = &mut self.heatmap {
+            if interaction.clicked {
+                if let Some(pos) = interaction.pos {
+                    heatmap.record(text, response.rect, pos);
+                }
+            }
+            heatmap.paint(ui.painter(), text, response.rect);
+        }
+
+        response
+    }
+
+    /// Key of the given `shape`, painted by the keyboard instead of the egui button frame.
+    fn add_shaped_key(
+        &self,
+        ui: &mut Ui,
+        text: &str,
+        selected: bool,
+        shape: KeyShape,
+        size: Vec2,
+    ) -> Response {
+        let (rect, response) = ui.allocate_exact_size(size, Sense::CLICK);
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().interact_selectable(&response, selected);
+            shape.paint(ui.painter(), rect, visuals.weak_bg_fill, visuals.bg_stroke);
+            ui.painter().text(
+                rect.center(),
+                Align2::CENTER_CENTER,
+                text,
+                self.style.text_style().resolve(ui.style()),
+                visuals.text_color(),
+            );
+        }
+        response
+    }
+
+    /// Button of a key, styled according to [`Keyboard::style`].
+    fn key_button_widget(&self, text: &str, button_size: Option<Vec2>) -> Button<'static> {
+        let text = RichText::new(text).text_style(self.style.text_style());
+        let mut button = button(text, button_size.unwrap_or(self.style.min_key_size));
+        if let Some(radius) = self.style.key_corner_radius {
+            button = button.corner_radius(radius);
+        }
+        button
+    }
+
+    fn key(
+        &mut self,
+        ui: &mut Ui,
+        text: &str,
+        actions: impl IntoIterator<Item = KeyAction>,
+        category: KeyCategory,
+        button_size: Option<Vec2>,
+    ) -> Response {
+        let response = self.add_key_button(ui, text, false, category, button_size);
+        if self.interaction(ui, &response).clicked {
+            self.perform(actions);
+            self.focus_back_to_input_widget(ui.ctx());
+        }
+        response
+    }
+
+    /// Do what the pressed key does, after mapping it according to the [`KeyMode`].
+    fn perform(&mut self, actions: impl IntoIterator<Item = KeyAction>) {
+        for action in actions {
+            match self.key_mode.map(action) {
+                KeyAction::Text(text) => self.push_events([Event::Text(text)]),
+                KeyAction::Key {
+                    key: egui::Key::Backspace,
+                    modifiers: Modifiers::NONE,
+                } if self.undo_correction() => {}
+                KeyAction::Key { key, modifiers } => {
+                    let key = self.visual_arrow(key);
+                    let clipboard_event = match key {
+                        _ if !modifiers.command => None,
+                        egui::Key::C => Some(Event::Copy),
+                        egui::Key::X => Some(Event::Cut),
+                        egui::Key::V => self.clipboard_text().map(Event::Paste),
+                        _ => None,
+                    };
+                    // Egui widgets expect the clipboard shortcuts translated by the integration,
+                    // like it does for a hardware keyboard.
+                    self.push_events(
+                        clipboard_event
+                            .into_iter()
+                            .chain([key_event(key, modifiers)]),
+                    );
+                }
+                KeyAction::Layer(layer) => self.layer = LayerState::new(layer),
+                KeyAction::Layout(index) => self.select_layout(index),
+                KeyAction::Callback(callback) => callback(),
+                KeyAction::None => {}
+            }
+        }
+    }
+
+    /// Arrow key moving the caret the way the `key` points, which is the opposite way in the text
+    /// of right-to-left layouts, see [`layouts::KeyboardLayoutBuilder::right_to_left`].
+    fn visual_arrow(&self, key: egui::Key) -> egui::Key {
+        if !self.keyboard_layout.is_right_to_left() {
+            return key;
+        }
+        match key {
+            egui::Key::ArrowLeft => egui::Key::ArrowRight,
+            egui::Key::ArrowRight => egui::Key::ArrowLeft,
+            key => key,
+        }
+    }
+
+    fn any_key(
+        &mut self,
+        ui: &mut Ui,
+        key: &Key,
+        pages_count: usize,
+        button_size: Vec2,
+    ) -> Response {
+        let size = Some(button_size);
+        if self.is_key_disabled(key) {
+            return self.disabled_key(ui, key, pages_count, size);
+        }
+        match key {
+            Key::Text(text) => {
+                let response = self.text_key(ui, text, size);
+                self.flick(ui, &response, text);
+                response
+            }
+            Key::Labeled { label, text } => {
+                let actions = [KeyAction::Text(text.to_string())];
+                self.key(ui, label, actions, KeyCategory::Character, size)
+            }
+            Key::Action { label, actions } => {
+                self.key(ui, label, actions.clone(), KeyCategory::Action, size)
+            }
+            Key::Backspace => {
+                let response = self.backspace_key(ui, size);
+                self.repeat_key(ui, &response, key);
+                response
+            }
+            Key::Upper => self.upper_layout_key(ui, size),
+            Key::Space => {
+                let text = [KeyAction::Text(" ".to_owned())];
+                let label = self.space_label().to_owned();
+                let response = self.key(ui, &label, text, KeyCategory::Space, size);
+                self.space_swipe(ui, &response);
+                self.repeat_key(ui, &response, key);
+                response
+            }
+            Key::Special => self.special_layout_key(ui, size),
+            Key::Selection(selection_key) => {
+                let (label, actions) = (selection_key.label(), selection_key.actions());
+                self.key(ui, label, actions, KeyCategory::Action, size)
+            }
+            Key::Page => self.page_key(ui, pages_count, size),
+            Key::Gap(_) => ui.allocate_exact_size(button_size, Sense::hover()).1,
+            Key::Emoji(_) => {
+                let label = self.key_label(key, pages_count);
+                let actions = [KeyAction::Text(label.clone())];
+                self.key(ui, &label, actions, KeyCategory::Character, size)
+            }
+            Key::EmojiTab(tab) => self.emoji_tab_key(ui, *tab, size),
+            Key::EmojiLayer => self.emoji_layer_key(ui, size),
+            Key::Globe => self.globe_key(ui, size),
+            Key::Peek => self.peek_key(ui, size),
+            Key::Equals => self.equals_key(ui, size),
+            Key::Group(texts) => self.group_key(ui, texts, size),
+            Key::Dictation => self.dictation_key(ui, size),
+            Key::Dismiss => self.dismiss_key(ui, size),
+            Key::Alternate {
+                key,
+                layer,
+                actions,
+            } if *layer == self.active_layer() => {
+                let label = self.key_label(key, pages_count);
+                self.key(ui, &label, actions.clone(), key.category(), size)
+            }
+            Key::Alternate { key, .. } | Key::Tall { key, .. } => {
+                self.any_key(ui, key, pages_count, button_size)
+            }
+            Key::Repeat(repeated) => {
+                let response = self.any_key(ui, repeated, pages_count, button_size);
+                self.repeat_key(ui, &response, repeated);
+                response
+            }
+            Key::WithAlternates {
+                key: inner,
+                alternates,
+            } => match self.key_actions(inner) {
+                Some(actions) => {
+                    let label = self.key_label(inner, pages_count);
+                    let alternates = alternates.clone();
+                    let response = self.alternates_key(
+                        ui,
+                        &label,
+                        actions,
+                        inner.category(),
+                        alternates,
+                        size,
+                    );
+                    if let Key::Text(text) = inner.as_ref() {
+                        self.flick(ui, &response, text);
+                    }
+                    response
+                }
+                // Keys switching the layers have nothing to offer instead.
+                None => self.any_key(ui, inner, pages_count, button_size),
+            },
+            Key::HoldToConfirm(inner) => match self.key_actions(inner) {
+                Some(actions) => {
+                    let label = self.key_label(inner, pages_count);
+                    self.confirm_key(ui, &label, actions, inner.category(), size)
+                }
+                None => self.any_key(ui, inner, pages_count, button_size),
+            },
+        }
+    }
+
+    /// Text shown on the key.
+    fn key_label(&self, key: &Key, pages_count: usize) -> String {
+        match key {
+            Key::Text(text) => self.digits.localize(text).into_owned(),
+            Key::Labeled { label, .. } | Key::Action { label, .. } => label.to_string(),
+            Key::Group(texts) => texts.concat(),
+            Key::Dictation => "🎤".to_owned(),
+            Key::Dismiss => "⏷".to_owned(),
+            Key::Backspace => self.backspace_character.to_string(),
+            Key::Upper if self.layer.upper => self.shift_characters[0].to_string(),
+            Key::Upper => self.shift_characters[1].to_string(),
+            Key::Space => self.space_label().to_owned(),
+            Key::Special if self.layer.special_locked => "🔒ABC".to_owned(),
+            Key::Special if self.layer.special => "ABC".to_owned(),
+            Key::Special => "!#1".to_owned(),
+            Key::Selection(selection_key) => selection_key.label().to_owned(),
+            Key::Page => format!("{}/{}", self.page + 1, pages_count),
+            Key::Gap(_) => String::new(),
+            Key::Emoji(index) => self
+                .emoji
+                .emojis
+                .get(*index)
+                .map(|emoji| emoji.emoji.clone())
+                .unwrap_or_default(),
+            Key::EmojiTab(tab) => self
+                .emoji
+                .tabs()
+                .get(*tab)
+                .and_then(|tab| self.emoji.emojis.get(tab.start))
+                .map(|emoji| emoji.emoji.clone())
+                .unwrap_or_default(),
+            Key::EmojiLayer if self.layer.emoji => "ABC".to_owned(),
+            Key::EmojiLayer => "☺".to_owned(),
+            Key::Globe => "🌐".to_owned(),
+            Key::Peek => "👁".to_owned(),
+            Key::Equals => "=".to_owned(),
+            Key::Alternate { key, .. }
+            | Key::Tall { key, .. }
+            | Key::Repeat(key)
+            | Key::WithAlternates { key, .. }
+            | Key::HoldToConfirm(key) => self.key_label(key, pages_count),
+        }
+    }
+
+    /// Layer which is currently shown.
+    fn active_layer(&self) -> Layer {
+        if self.layer.emoji {
+            Layer::Emoji
+        } else if self.layer.special {
+            Layer::Special
+        } else if self.layer.upper || self.shift_held {
+            Layer::Uppercase
+        } else {
+            Layer::Lowercase
+        }
+    }
+
+    /// Colors laid over the style for the shown layer, the ones of the layout first, see
+    /// [`KeyboardStyle::layer_styles`].
+    fn layer_style(&self) -> LayerStyle {
+        let layer = self.active_layer();
+        let style = self
+            .style
+            .layer_styles
+            .get(&layer)
+            .copied()
+            .unwrap_or_default();
+        self.keyboard_layout.layer_style(layer).or(style)
+    }
+
+    fn upper_layout_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) -> Response {
+        let text = self.key_label(&Key::Upper, 0);
+        let selected = self.layer.upper || self.shift_held;
+        let response = self.add_key_button(ui, &text, selected, KeyCategory::Modifier, button_size);
+        let interaction = self.interaction(ui, &response);
+        self.shift_held = interaction.down;
+        if interaction.clicked {
+            // Shift was only held to type some letters with another finger.
+            if !std::mem::take(&mut self.typed_while_shift_held) {
+                self.layer.upper = !self.layer.upper;
+            }
+            self.focus_back_to_input_widget(ui.ctx());
+        }
+        response
+    }
+
+    fn special_layout_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) -> Response {
+        let text = self.key_label(&Key::Special, 0);
+        let selected = self.layer.special_locked;
+        let response = self.add_key_button(ui, &text, selected, KeyCategory::Modifier, button_size);
+        match self.press(ui, &response) {
+            Press::None => {}
+            // Locked layer can be only left by another long press.
+            Press::Click if self.layer.special_locked => {}
+            Press::Click => self.layer.special = !self.layer.special,
+            Press::Long => {
+                self.layer.special_locked = !self.layer.special_locked;
+                self.layer.special = self.layer.special_locked;
+            }
+        }
+        if self.interaction(ui, &response).clicked {
+            self.focus_back_to_input_widget(ui.ctx());
+        }
+        response
+    }
+
+    /// Tell a click from a long press. Long press is reported as soon as the key is held long
+    /// enough, and the click which ends it is swallowed. A click with the secondary mouse button
+    /// is reported as a long press too.
+    fn press(&mut self, ui: &Ui, response: &Response) -> Press {
+        let interaction = self.interaction(ui, response);
+        if interaction.down && self.gestures_disabled {
+            return Press::None;
+        }
+        if interaction.down {
+            let duration = self.long_press_duration.unwrap_or(LONG_PRESS_DURATION);
+            if interaction.held >= duration && self.long_pressed != Some(response.id) {
+                self.long_pressed = Some(response.id);
+                return Press::Long;
+            }
+            return Press::None;
+        }
+
+        let was_long_pressed = self.long_pressed == Some(response.id);
+        if was_long_pressed {
+            self.long_pressed = None;
+        }
+
+        if interaction.clicked && !was_long_pressed {
+            Press::Click
+        } else if interaction.secondary_clicked && !was_long_pressed && !self.gestures_disabled {
+            // Mouse users can hardly long-press, so a right click does the same.
+            Press::Long
+        } else {
+            Press::None
+        }
+    }
+
+    fn emoji_tab_key(&mut self, ui: &mut Ui, tab: usize, button_size: Option<Vec2>) -> Response {
+        let text = self.key_label(&Key::EmojiTab(tab), 0);
+        let selected = self.layer.emoji_tab == tab;
+        let response = self.add_key_button(ui, &text, selected, KeyCategory::Modifier, button_size);
+        if self.interaction(ui, &response).clicked {
+            self.layer.emoji_tab = tab;
+            self.focus_back_to_input_widget(ui.ctx());
+        }
+        response
+    }
+
+    fn emoji_layer_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) -> Response {
+        let text = self.key_label(&Key::EmojiLayer, 0);
+        let response = self.add_key_button(ui, &text, false, KeyCategory::Modifier, button_size);
+        if self.interaction(ui, &response).clicked {
+            self.layer.emoji = !self.layer.emoji;
+            self.focus_back_to_input_widget(ui.ctx());
+        }
+        response
+    }
+
+    fn page_key(&mut self, ui: &mut Ui, pages_count: usize, button_size: Option<Vec2>) -> Response {
+        let text = self.key_label(&Key::Page, pages_count);
+        let response = self.add_key_button(ui, &text, false, KeyCategory::Modifier, button_size);
+        if self.interaction(ui, &response).clicked {
+            self.page = (self.page + 1) % pages_count;
+            self.focus_back_to_input_widget(ui.ctx());
+        }
+        response
+    }
+
+    fn backspace_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) -> Response {
+        self.key(
+            ui,
+            &self.backspace_character.to_string(),
+            [KeyAction::Key {
+                key: egui::Key::Backspace,
+                modifiers: Modifiers::NONE,
+            }],
+            KeyCategory::Action,
+            button_size,
+        )
+    }
+
+    fn text_key(&mut self, ui: &mut Ui, text: &str, button_size: Option<Vec2>) -> Response {
+        let text = &self.digits.localize(text).into_owned();
+        let actions = [KeyAction::Text(text.to_string())];
+        let alternates = self.keyboard_layout.alternates(text);
+        if alternates.is_empty() {
+            return self.key(ui, text, actions, KeyCategory::Character, button_size);
+        }
+
+        self.alternates_key(
+            ui,
+            text,
+            actions.to_vec(),
+            KeyCategory::Character,
+            alternates,
+            button_size,
+        )
+    }
+
+    /// Key doing the `actions` when tapped and offering the `alternates` when long-pressed. The
+    /// text it types, if any, is offered first.
+    fn alternates_key(
+        &mut self,
+        ui: &mut Ui,
+        label: &str,
+        actions: Vec<KeyAction>,
+        category: KeyCategory,
+        alternates: Vec<String>,
+        button_size: Option<Vec2>,
+    ) -> Response {
+        let response = self.add_key_button(ui, label, false, category, button_size);
+        if self.style.alternate_hints && !self.gestures_disabled {
+            if let Some(hint) = alternates.first() {
+                alternates::paint_hint(ui, response.rect, hint);
+            }
+        }
+        match self.press(ui, &response) {
+            Press::None => {}
+            Press::Click => {
+                self.perform(actions);
+                self.focus_back_to_input_widget(ui.ctx());
+            }
+            Press::Long => {
+                let text = match actions.as_slice() {
+                    [KeyAction::Text(text)] => Some(text.clone()),
+                    _ => None,
+                };
+                let options = text.into_iter().chain(alternates).collect();
+                self.alternates_popup = Some(alternates::AlternatesPopup::new(
+                    response.id,
+                    response.rect,
+                    options,
+                ));
+            }
+        }
+        self.slide_over_alternates(ui.ctx(), response.id);
+        response
+    }
+
+    /// Handle taps of fingers other than the one driving the egui pointer. Egui buttons only react
+    /// to the pointer, so holding shift with one finger while tapping the letters with another
+    /// would not work otherwise.
+    fn handle_secondary_touches(&mut self, ctx: &Context) {
+        let touches: Vec<_> = ctx.input(|input| {
+            input
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::Touch { id, phase, pos, .. } => Some((id.0, *phase, *pos)),
+                    _ => None,
+                })
+                .collect()
+        });
+
+        for (id, phase, pos) in touches {
+            match phase {
+                TouchPhase::Start => {
+                    self.touches.insert(id, pos);
+                }
+                TouchPhase::Move => {}
+                TouchPhase::End => {
+                    if let Some(start) = self.touches.remove(&id) {
+                        if self.shift_held {
+                            self.secondary_tap(ctx, start, pos);
+                        }
+                    }
+                }
+                TouchPhase::Cancel => {
+                    self.touches.remove(&id);
+                }
+            }
+        }
+    }
+
+    /// Press the key which was tapped by a secondary finger, if the tap started and ended on it.
+    fn secondary_tap(&mut self, ctx: &Context, start: Pos2, end: Pos2) {
+        let Some(key) = self
+            .key_rects
+            .iter()
+            .find(|(rect, _)| rect.contains(start) && rect.contains(end))
+            .map(|(_, key)| key)
+        else {
+            return;
+        };
+        if self.is_key_disabled(key) {
+            return;
+        }
+        let Some(actions) = self.key_actions(key) else {
+            return;
+        };
+
+        self.perform(actions);
+        self.typed_while_shift_held = true;
+        self.focus_back_to_input_widget(ctx);
+    }
+
+    /// What the `key` does when tapped, if it types or deletes something. Keys which need to be
+    /// held to confirm do nothing when tapped.
+    fn key_actions(&self, key: &Key) -> Option<Vec<KeyAction>> {
+        let actions = match key {
+            Key::Tall { key, .. } | Key::Repeat(key) | Key::WithAlternates { key, .. } => {
+                return self.key_actions(key)
+            }
+            Key::Alternate { layer, actions, .. } if *layer == self.active_layer() => {
+                actions.clone()
+            }
+            Key::HoldToConfirm(_) => return None,
+            key => match key.base() {
+                Key::Text(text) => vec![KeyAction::Text(self.digits.localize(text).into_owned())],
+                Key::Labeled { text, .. } => vec![KeyAction::Text(text.to_string())],
+                Key::Action { actions, .. } => actions.clone(),
+                Key::Space => vec![KeyAction::Text(" ".to_owned())],
+                Key::Emoji(_) => vec![KeyAction::Text(self.key_label(key, 0))],
+                Key::Backspace => vec![KeyAction::Key {
+                    key: egui::Key::Backspace,
+                    modifiers: Modifiers::NONE,
+                }],
+                _ => return None,
+            },
+        };
+        Some(actions)
+    }
+
+    fn keyboard_input_needed(&mut self, ctx: &Context) -> bool {
+        let needed = if !self.focus_in_scope(ctx) {
+            // Focus moved to a part of the application the keyboard does not type into.
+            self.needed = 0;
+            false
+        } else if ctx.wants_keyboard_input() {
+            self.needed = 20;
+            true
+        } else {
+            self.needed = self.needed.saturating_sub(1);
+            self.needed > 0
+        };
+
+        if needed {
+            ctx.request_repaint();
+        }
+
+        self.free_typing_active = !needed && self.free_typing.is_some();
+        needed || self.free_typing_active
     }
 }
 
@@ -0,0 +1,227 @@
+//! Checks for suspicious layout definitions.
+
+use crate::layouts::{KeyboardLayout, Layer, LayoutFeature};
+use crate::{Key, KeyAction, KeyMode, Keyboard, SPACE_BETWEEN_KEYS};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Default budget for [`KeyboardLayout::lint`], in widths of a regular key. Fits the widest row
+/// of the built-in layouts, ten letters between the shift and the backspace keys of Colemak,
+/// with a bit of slack.
+pub const DEFAULT_ROW_WIDTH_BUDGET: f32 = 16.0;
+
+/// Suspicious thing found in a layout by [`KeyboardLayout::lint`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum LintWarning {
+    /// The same text is emitted by more than one key of the layer.
+    DuplicateKey { layer: Layer, text: String },
+    /// No key toggles into the layer, so the user can never see it.
+    UnreachableLayer { layer: Layer },
+    /// The layer has no backspace key.
+    MissingBackspace { layer: Layer },
+    /// The layer has no space key.
+    MissingSpace { layer: Layer },
+    /// The row is wider than the budget, so its keys will be squeezed.
+    RowTooWide {
+        layer: Layer,
+        row: usize,
+        width: f32,
+    },
+    /// The layer relies on a capability the keyboard is configured without, see
+    /// [`Keyboard::validate`].
+    MissingFeature {
+        layer: Layer,
+        feature: LayoutFeature,
+    },
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateKey { layer, text } => {
+                write!(f, "{layer:?} layer has more than one {text:?} key")
+            }
+            Self::UnreachableLayer { layer } => {
+                write!(f, "no key toggles into the {layer:?} layer")
+            }
+            Self::MissingBackspace { layer } => write!(f, "{layer:?} layer has no backspace"),
+            Self::MissingSpace { layer } => write!(f, "{layer:?} layer has no space"),
+            Self::RowTooWide { layer, row, width } => write!(
+                f,
+                "row {row} of the {layer:?} layer is {width:.2} keys wide"
+            ),
+            Self::MissingFeature { layer, feature } => write!(
+                f,
+                "{layer:?} layer relies on {feature:?}, which the keyboard is configured without"
+            ),
+        }
+    }
+}
+
+impl KeyboardLayout {
+    /// Look for suspicious definitions in the layout, using [`DEFAULT_ROW_WIDTH_BUDGET`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui_keyboard::layouts::KeyboardLayout;
+    ///
+    /// for layout in [KeyboardLayout::Qwerty, KeyboardLayout::Colemak] {
+    ///     assert_eq!(layout.lint(), []);
+    /// }
+    /// ```
+    pub fn lint(&self) -> Vec<LintWarning> {
+        self.lint_with_row_width_budget(DEFAULT_ROW_WIDTH_BUDGET)
+    }
+
+    /// Look for suspicious definitions in the layout. Rows wider than `row_width_budget`, given in
+    /// widths of a regular key including the spaces between keys, are reported.
+    pub fn lint_with_row_width_budget(&self, row_width_budget: f32) -> Vec<LintWarning> {
+        let mut warnings = Vec::new();
+
+        for &layer in self.layers() {
+            let keys = self.layer_keys(layer);
+
+            let mut seen = HashSet::new();
+            for key in keys.iter().flatten() {
+                if let Key::Text(text) | Key::Labeled { text, .. } = key.base() {
+                    if !seen.insert(text.as_str()) {
+                        warnings.push(LintWarning::DuplicateKey {
+                            layer,
+                            text: text.to_string(),
+                        });
+                    }
+                }
+            }
+
+            if !keys
+                .iter()
+                .flatten()
+                .any(|key| matches!(key.base(), Key::Backspace))
+            {
+                warnings.push(LintWarning::MissingBackspace { layer });
+            }
+
+            if !keys.iter().flatten().any(|key| match key.base() {
+                Key::Space => true,
+                Key::Text(text) => text == " ",
+                _ => false,
+            }) {
+                warnings.push(LintWarning::MissingSpace { layer });
+            }
+
+            for (row, row_keys) in keys.iter().enumerate() {
+                let width = row_keys.width_relative(SPACE_BETWEEN_KEYS);
+                if width > row_width_budget {
+                    warnings.push(LintWarning::RowTooWide { layer, row, width });
+                }
+            }
+        }
+
+        let reachable = self.reachable_layers();
+        for &layer in self.layers() {
+            if !reachable.contains(&layer) {
+                warnings.push(LintWarning::UnreachableLayer { layer });
+            }
+        }
+
+        warnings
+    }
+
+    /// Layers which can be reached from the initial, lowercase one.
+    fn reachable_layers(&self) -> HashSet<Layer> {
+        let mut reachable = HashSet::from([Layer::Lowercase]);
+        let mut pending = vec![Layer::Lowercase];
+
+        while let Some(layer) = pending.pop() {
+            for key in self.layer_keys(layer).iter().flatten() {
+                for target in switched_layers(key, layer) {
+                    if self.layers().contains(&target) && reachable.insert(target) {
+                        pending.push(target);
+                    }
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+/// Layers the `key` switches to from the `layer`.
+fn switched_layers(key: &Key, layer: Layer) -> Vec<Layer> {
+    let actions_layers = |actions: &[KeyAction]| -> Vec<Layer> {
+        actions
+            .iter()
+            .filter_map(|action| match action {
+                KeyAction::Layer(layer) => Some(*layer),
+                _ => None,
+            })
+            .collect()
+    };
+    match key {
+        Key::Upper if layer == Layer::Uppercase => vec![Layer::Lowercase],
+        Key::Upper => vec![Layer::Uppercase],
+        Key::Special if layer == Layer::Special => vec![Layer::Lowercase],
+        Key::Special => vec![Layer::Special],
+        Key::EmojiLayer if layer == Layer::Emoji => vec![Layer::Lowercase],
+        Key::EmojiLayer => vec![Layer::Emoji],
+        Key::Action { actions, .. } => actions_layers(actions),
+        Key::Alternate { key, actions, .. } => {
+            let mut layers = switched_layers(key, layer);
+            layers.extend(actions_layers(actions));
+            layers
+        }
+        Key::Tall { key, .. }
+        | Key::Repeat(key)
+        | Key::WithAlternates { key, .. }
+        | Key::HoldToConfirm(key) => switched_layers(key, layer),
+        _ => Vec::new(),
+    }
+}
+
+impl Keyboard {
+    /// Look for suspicious definitions in the current layout, see [`KeyboardLayout::lint`], and
+    /// for the capabilities its layers rely on which the keyboard is configured without, see
+    /// [`KeyboardLayout::features`]. Without them, some keys do nothing or can not be reached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use egui_keyboard::layouts::{KeyboardLayoutBuilder, LayoutFeature, LayoutKey};
+    /// use egui_keyboard::lint::LintWarning;
+    ///
+    /// let layout = KeyboardLayoutBuilder::new()
+    ///     .row(vec![LayoutKey::text("e").with_alternates(["é", "ě"]), LayoutKey::Backspace])
+    ///     .row(vec![LayoutKey::Space])
+    ///     .build();
+    /// let keyboard = egui_keyboard::Keyboard::default().layout(layout).gestures(false);
+    /// assert!(keyboard.validate().contains(&LintWarning::MissingFeature {
+    ///     layer: egui_keyboard::layouts::Layer::Lowercase,
+    ///     feature: LayoutFeature::LongPress,
+    /// }));
+    /// ```
+    pub fn validate(&self) -> Vec<LintWarning> {
+        let layout = &self.keyboard_layout;
+        let mut warnings = layout.lint();
+        for &layer in layout.layers() {
+            for feature in layout.features(layer) {
+                if !self.has_feature(feature) {
+                    warnings.push(LintWarning::MissingFeature { layer, feature });
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Whether the keyboard is configured with the capability.
+    fn has_feature(&self, feature: LayoutFeature) -> bool {
+        match feature {
+            LayoutFeature::LongPress => !self.gestures_disabled,
+            LayoutFeature::Pages => {
+                self.accessibility_scale.is_some_and(|scale| scale > 1.0)
+                    || self.display_dpi.is_some()
+            }
+            LayoutFeature::InputMethod => matches!(self.key_mode, KeyMode::Custom(_)),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Loading the keyboard settings from files.
+
+use ron::extensions::Extensions;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Error loading the keyboard settings from a file.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not valid RON, or does not describe the settings.
+    Parse(ron::error::SpannedError),
+    /// The file is not valid JSON, or does not describe the settings.
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "cannot read the file: {err}"),
+            Self::Parse(err) => write!(f, "cannot parse the file: {err}"),
+            Self::Json(err) => write!(f, "cannot parse the file: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Parse(err) => Some(err),
+            Self::Json(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for LoadError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+pub(crate) fn from_file<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, LoadError> {
+    from_ron(&std::fs::read_to_string(path)?)
+}
+
+/// Parse the settings, allowing to leave out `Some` around optional fields and the extra
+/// parentheses around the colors.
+pub(crate) fn from_ron<T: DeserializeOwned>(ron: &str) -> Result<T, LoadError> {
+    let options = ron::Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME | Extensions::UNWRAP_NEWTYPES);
+    Ok(options.from_str(ron)?)
+}
+
+pub(crate) fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, LoadError> {
+    Ok(serde_json::from_str(json)?)
+}
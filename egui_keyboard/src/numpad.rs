@@ -0,0 +1,82 @@
+//! Numpad shown next to the keyboard, for typing both letters and digits a lot.
+
+use crate::{layouts, Handedness, InputHint, Keyboard, ScrollBehavior, WINDOW_TITLE};
+use egui::{pos2, Align2, Context, Frame, Id, LayerId, Order, Response};
+
+/// Width of the numpad when it is shown for the first time, relative to the screen.
+const NUMPAD_WIDTH_FRACTION: f32 = 0.3;
+
+/// Height of the keys of the numpad relative to its width, so they are roughly square.
+const NUMPAD_HEIGHT_RATIO: f32 = 1.0;
+
+/// Id of the numpad window.
+fn numpad_id() -> Id {
+    Id::new(WINDOW_TITLE).with("numpad")
+}
+
+/// Layer of the numpad window.
+pub(crate) fn numpad_layer() -> LayerId {
+    LayerId::new(Order::Foreground, numpad_id())
+}
+
+impl Keyboard {
+    /// Show a small numpad in a window of its own next to the keyboard, which the user can move
+    /// around, e.g. in a spreadsheet where both the letters and the digits are typed a lot. It
+    /// types into the same widget as the keyboard and is hidden along with it, or while the
+    /// keyboard shows the numeric keys anyway.
+    pub fn numpad_companion(mut self, enabled: bool) -> Self {
+        self.numpad_companion = enabled;
+        self
+    }
+
+    /// Show or hide the numpad next to the keyboard, e.g. from a toolbar of the application.
+    pub fn set_numpad_companion(&mut self, enabled: bool) {
+        self.numpad_companion = enabled;
+    }
+
+    /// Window with the numpad, above the keyboard on the side of the dominant hand until the
+    /// user moves it.
+    pub(crate) fn show_numpad_companion(
+        &mut self,
+        ctx: &Context,
+        frame: Frame,
+        away: f32,
+        needed: bool,
+    ) -> Option<Response> {
+        if !self.numpad_companion || self.focused_input_hint() == InputHint::Numeric {
+            return None;
+        }
+        let mut rows = layouts::numeric();
+        self.remove_hidden_keys(&mut rows);
+
+        let screen = ctx.screen_rect();
+        let (pivot, x) = match self.handedness {
+            Handedness::Right => (Align2::RIGHT_BOTTOM, screen.right()),
+            Handedness::Left => (Align2::LEFT_BOTTOM, screen.left()),
+        };
+        let opacity = match self.scroll_behavior {
+            ScrollBehavior::SlideAway => self.visibility * (1. - away),
+            _ => self.visibility,
+        };
+        let width = screen.width() * NUMPAD_WIDTH_FRACTION;
+        // The numpad has a single page, which must not reset the page of the keyboard.
+        let page = self.page;
+        let response = self
+            .keyboard_window(
+                numpad_id(),
+                frame.multiply_with_opacity(opacity),
+                away,
+                needed,
+            )
+            .pivot(pivot)
+            .default_pos(pos2(x, screen.bottom() - self.covered_height))
+            .fixed_size([width, 0.0])
+            .show(ctx, |ui| {
+                ui.multiply_opacity(opacity);
+                self.keys_ui(ui, rows, width * NUMPAD_HEIGHT_RATIO, away, false);
+            })
+            .map(|response| response.response);
+        self.page = page;
+        response
+    }
+}
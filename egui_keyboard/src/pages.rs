@@ -0,0 +1,62 @@
+//! Splitting layouts which are too wide into narrower rows and pages.
+
+use crate::layouts::Row;
+use crate::{row_width_relative, Key};
+
+/// Split the rows wider than `budget` (relative to a regular key) into narrower ones. If that
+/// makes the layout taller, the rows are spread across pages, each keeping the original number of
+/// rows. The last row, usually holding the space key, is shown on every page, together with
+/// a key switching to the next page.
+pub(crate) fn paginate(mut rows: Vec<Row>, budget: f32, spacing: f32) -> Vec<Vec<Row>> {
+    if rows.len() < 2 || rows.iter().all(|row| row.width_relative(spacing) <= budget) {
+        return vec![rows];
+    }
+
+    let rows_per_page = rows.len() - 1;
+    let mut last_row = rows
+        .pop()
+        .map(|row| split_row(row, budget, spacing))
+        .unwrap_or_default();
+    let rows: Vec<Row> = rows
+        .into_iter()
+        .flat_map(|row| split_row(row, budget, spacing))
+        .collect();
+
+    if rows.len() <= rows_per_page {
+        return vec![rows.into_iter().chain(last_row).collect()];
+    }
+
+    if let Some(row) = last_row.last_mut() {
+        row.keys.push(Key::Page);
+    }
+
+    rows.chunks(rows_per_page)
+        .map(|page| {
+            page.iter()
+                .cloned()
+                .chain(last_row.iter().cloned())
+                .collect()
+        })
+        .collect()
+}
+
+/// Split the row into as few rows as possible, each fitting into the `budget` and keeping the
+/// alignment of the original row.
+fn split_row(row: Row, budget: f32, spacing: f32) -> Vec<Row> {
+    let mut rows = vec![Vec::new()];
+    for key in row.keys {
+        let current = rows.last_mut().expect("there is always at least one row");
+        current.push(key);
+        if current.len() > 1 && row.offset + row_width_relative(current, spacing) > budget {
+            let key = current.pop().expect("the key was just pushed");
+            rows.push(vec![key]);
+        }
+    }
+    rows.into_iter()
+        .map(|keys| Row {
+            keys,
+            align: row.align,
+            offset: row.offset,
+        })
+        .collect()
+}
@@ -0,0 +1,46 @@
+//! Revealing the focused password while a key is held.
+
+use crate::{KeyCategory, Keyboard};
+use egui::{Id, Response, Ui, Vec2};
+
+impl Keyboard {
+    /// Offer a key in the password fields, see [`crate::InputHint::Password`], which reveals the
+    /// password while it is held. The keyboard does not see the text of the field, so it calls
+    /// `callback` with the id of the field and `true` when the key is pressed, and the
+    /// application shows the text unmasked, e.g. with `TextEdit::password(false)`.
+    ///
+    /// Every `true` is followed by a call with `false`, when the key is released, the field
+    /// loses focus, the keyboard is hidden or disabled, or [`Keyboard::reset`] is called. The
+    /// application masks the text again then. The field stays a password field while revealed,
+    /// so its text is still never offered in the suggestions.
+    pub fn password_peek(mut self, callback: impl FnMut(Id, bool) + 'static) -> Self {
+        self.password_peek = Some(Box::new(callback));
+        self
+    }
+
+    pub(crate) fn peek_key(&mut self, ui: &mut Ui, button_size: Option<Vec2>) -> Response {
+        let peeking = self.peeking.is_some();
+        let response = self.add_key_button(ui, "👁", peeking, KeyCategory::Modifier, button_size);
+        let down = self.interaction(ui, &response).down;
+        match (down, self.input_widget) {
+            (true, Some(widget)) if !peeking => {
+                if let Some(callback) = &mut self.password_peek {
+                    callback(widget, true);
+                }
+                self.peeking = Some(widget);
+            }
+            (false, _) if peeking => self.stop_peeking(),
+            _ => {}
+        }
+        response
+    }
+
+    /// Mask the revealed password again, if any.
+    pub(crate) fn stop_peeking(&mut self) {
+        if let Some(widget) = self.peeking.take() {
+            if let Some(callback) = &mut self.password_peek {
+                callback(widget, false);
+            }
+        }
+    }
+}
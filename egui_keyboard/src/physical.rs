@@ -0,0 +1,61 @@
+//! Sizing the keys in millimeters, so they are equally easy to hit on any display.
+
+use crate::Keyboard;
+
+/// Keys smaller than this are hard to hit with a finger, in millimeters.
+pub(crate) const MIN_KEY_SIZE_MM: f32 = 9.0;
+
+const MM_PER_INCH: f32 = 25.4;
+
+impl Keyboard {
+    /// Physical density of the display in pixels per inch, e.g. computed from its resolution and
+    /// its width in millimeters as `width_px / width_mm * 25.4`. The keys are then enlarged to
+    /// be at least [`Keyboard::min_key_size_mm`] big, like with [`Keyboard::accessibility_scale`].
+    pub fn display_dpi(mut self, dpi: f32) -> Self {
+        self.set_display_dpi(dpi);
+        self
+    }
+
+    /// Change the physical density of the display, e.g. when the window moves to another one.
+    pub fn set_display_dpi(&mut self, dpi: f32) {
+        self.display_dpi = Some(dpi).filter(|dpi| *dpi > 0.0);
+    }
+
+    /// Smallest width and height of the keys in millimeters when the density of the display is
+    /// known, see [`Keyboard::display_dpi`]. 9 mm if not set.
+    pub fn min_key_size_mm(mut self, mm: f32) -> Self {
+        self.min_key_size_mm = Some(mm.max(0.0));
+        self
+    }
+
+    /// Smallest size of the keys in points, if the density of the display is known.
+    fn min_key_points(&self, pixels_per_point: f32) -> Option<f32> {
+        let dpi = self.display_dpi?;
+        let mm = self.min_key_size_mm.unwrap_or(MIN_KEY_SIZE_MM);
+        Some(mm / MM_PER_INCH * dpi / pixels_per_point)
+    }
+
+    /// How much to enlarge the keys, so they are at least as big as the accessibility scale
+    /// and the minimum physical size ask for. The keys are `rows_count` rows of `widest_row`
+    /// regular keys taking the `width` and the `height` before they are enlarged.
+    pub(crate) fn key_scale(
+        &self,
+        pixels_per_point: f32,
+        rows_count: f32,
+        widest_row: f32,
+        width: f32,
+        height: f32,
+    ) -> f32 {
+        let scale = self.accessibility_scale.unwrap_or(1.0);
+        let Some(min_points) = self.min_key_points(pixels_per_point) else {
+            return scale;
+        };
+        if rows_count == 0.0 || widest_row == 0.0 {
+            return scale;
+        }
+        let spacing = self.style.key_spacing;
+        let key_height = height / ((rows_count - 1.0) * spacing + rows_count);
+        let key_width = width / widest_row;
+        scale.max(min_points / key_height.min(key_width))
+    }
+}
@@ -0,0 +1,432 @@
+//! Where the keyboard is shown on the screen.
+
+use crate::layouts::{self, Row};
+use crate::{Handedness, Keyboard, ScrollBehavior, WINDOW_TITLE};
+use egui::{
+    pos2, vec2, Align2, Context, Frame, Id, Order, Pos2, Rangef, Rect, Response, TopBottomPanel,
+    Ui, Vec2, Window,
+};
+
+/// Width of the floating keyboard when it is shown for the first time, relative to the screen.
+const FLOATING_WIDTH_FRACTION: f32 = 0.6;
+
+/// Height of the keys of the floating keyboard relative to its width, so resizing it keeps the
+/// proportions of the keys. The same goes for the keyboard following a widget.
+const FLOATING_HEIGHT_RATIO: f32 = 0.4;
+
+/// Width of the keyboard following a widget, relative to the screen, unless the widget is wider.
+const FOLLOW_WIDTH_FRACTION: f32 = 0.5;
+
+/// Width of each half of the split keyboard, relative to the screen.
+const SPLIT_WIDTH_FRACTION: f32 = 0.4;
+
+/// Longest wait for the screen to settle before the keyboard is shown, in frames.
+const MAX_SETTLE_FRAMES: u32 = 30;
+
+/// Whether the screen settled for the keyboard to be shown, see [`Keyboard::settle_frames`].
+#[derive(Default)]
+pub(crate) struct Settling {
+    /// Available rect of the screen and the rect of the focused widget in the last frame.
+    rects: Option<(Rect, Option<Rect>)>,
+    /// For how many frames the rects stayed the same.
+    stable: u32,
+    /// For how many frames the keyboard waits.
+    waited: u32,
+}
+
+/// Where the keyboard is shown by [`Keyboard::show`]. Use [`Keyboard::show_inside`] to put it
+/// in a container of the application instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum KeyboardPlacement {
+    /// Window over the bottom of the screen, covering the content. Use [`Keyboard::safe_rect`]
+    /// to keep the content out of it.
+    #[default]
+    Overlay,
+    /// Panel at the bottom of the screen, the central panel is resized to make room for it.
+    /// [`Keyboard::show`] needs to be called before the central panel is shown. It does not
+    /// slide away with [`ScrollBehavior::SlideAway`], as that would resize the content being
+    /// scrolled.
+    Docked,
+    /// Window which the user can move and resize, e.g. on a tablet. It fades in and out
+    /// instead of sliding.
+    Floating,
+    /// Left and right halves of the keys anchored to the screen edges, for typing with thumbs.
+    Split,
+    /// Window right below the rect given with [`Keyboard::follow`], or the focused widget if
+    /// there is none, moving along with it, e.g. a small numpad next to the field being edited.
+    /// It goes above or beside the rect when there is no room below it. As it moves along with
+    /// the content, [`Keyboard::safe_rect`] does not make room for it.
+    Follow,
+}
+
+impl Keyboard {
+    /// Where the keyboard is shown, see [`KeyboardPlacement`].
+    pub fn placement(mut self, placement: KeyboardPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Change where the keyboard is shown, e.g. from a settings screen of the application.
+    pub fn set_placement(&mut self, placement: KeyboardPlacement) {
+        self.placement = placement;
+    }
+
+    /// Keep the keyboard off the `rect`, e.g. a status bar which must stay visible. The keyboard
+    /// anchored to the bottom of the screen moves above the rects in the lower half of the
+    /// screen and shrinks to fit below the ones in the upper half. The floating keyboard cannot
+    /// be dragged over them. [`Keyboard::safe_rect`] reflects where the keyboard ends up.
+    ///
+    /// The rect is only avoided in the current frame, so this needs to be called every frame
+    /// before [`Keyboard::show`].
+    pub fn avoid(&mut self, rect: Rect) {
+        self.avoided.push(rect);
+    }
+
+    /// Show the keyboard next to the `rect` with [`KeyboardPlacement::Follow`], e.g. the rect
+    /// of a window holding the fields being edited.
+    ///
+    /// The rect is only followed in the current frame, so this needs to be called every frame
+    /// before [`Keyboard::show`].
+    pub fn follow(&mut self, rect: Rect) {
+        self.followed = Some(rect);
+    }
+
+    /// Whether the keyboard which is `needed` can be shown, once the screen and the focused widget
+    /// stay in place, see [`Keyboard::settle_frames`]. The keyboard which is already shown stays.
+    pub(crate) fn settle(&mut self, ctx: &Context, needed: bool) -> bool {
+        if !needed {
+            self.settling = Settling::default();
+            return false;
+        }
+        if self.settle_frames == 0 || self.visibility > 0. {
+            return true;
+        }
+        let focused = ctx
+            .memory(|memory| memory.focused())
+            .and_then(|id| ctx.read_response(id))
+            .map(|response| response.rect);
+        let rects = Some((ctx.available_rect(), focused));
+        let settling = &mut self.settling;
+        if settling.rects == rects {
+            settling.stable += 1;
+        } else {
+            settling.rects = rects;
+            settling.stable = 0;
+        }
+        settling.waited += 1;
+        let settled = settling.stable >= self.settle_frames || settling.waited > MAX_SETTLE_FRAMES;
+        if !settled {
+            ctx.request_repaint();
+        }
+        settled
+    }
+
+    /// Shows the virtual keyboard if needed inside the `ui`, e.g. in a panel or a window of the
+    /// application, taking its whole width. The [`Keyboard::placement`] and the
+    /// [`Keyboard::scroll_behavior`] do not apply then. The area above the keyboard is still
+    /// reported as [`Keyboard::safe_rect`].
+    pub fn show_inside(&mut self, ui: &mut Ui) {
+        let ctx = ui.ctx().clone();
+        let needed = self.begin_show(&ctx);
+
+        let mut shown = Vec::new();
+        if self.visibility > 0. {
+            let keys = self.keys_to_show(&ctx);
+            let height = ui
+                .available_height()
+                .min(ctx.screen_rect().height() * self.style.height_fraction);
+            let opacity = self.visibility;
+            let response = self
+                .keyboard_frame(&ctx)
+                .multiply_with_opacity(opacity)
+                .show(ui, |ui| {
+                    ui.multiply_opacity(opacity);
+                    self.keys_ui(ui, keys, height, 0., true);
+                })
+                .response;
+            self.covered_height = (ctx.screen_rect().bottom() - response.rect.top()).max(0.);
+            shown.push(response);
+        }
+        self.end_show(&ctx, needed, &shown);
+    }
+
+    /// Full width window over the bottom of the screen.
+    pub(crate) fn show_overlay(
+        &mut self,
+        ctx: &Context,
+        keys: Vec<Row>,
+        frame: Frame,
+        away: f32,
+        needed: bool,
+    ) -> Vec<Response> {
+        let available = ctx.available_rect();
+        let band = self.free_band(ctx);
+        let width = available.width() - frame.total_margin().sum().x;
+        let height = available.height().min(band.span()) * self.style.height_fraction;
+        let lift = ctx.screen_rect().bottom() - band.max;
+        let shown: Vec<Response> = self
+            .keyboard_window(Id::new(WINDOW_TITLE), frame, away, needed)
+            .anchor(
+                Align2::CENTER_BOTTOM,
+                vec2(0., self.slide_offset(ctx, away) - lift),
+            )
+            .fixed_size(vec2(width, 0.))
+            .show(ctx, |ui| self.keys_ui(ui, keys, height, away, true))
+            .map(|response| response.response)
+            .into_iter()
+            .collect();
+        self.covered_height = self.slid_in_height(&shown) + lift;
+        shown
+    }
+
+    /// Panel at the bottom of the screen, see [`KeyboardPlacement::Docked`].
+    pub(crate) fn show_docked(
+        &mut self,
+        ctx: &Context,
+        keys: Vec<Row>,
+        frame: Frame,
+        away: f32,
+    ) -> Vec<Response> {
+        // Panels shown before the keyboard are below it already, so it can only shrink.
+        let height = ctx
+            .available_rect()
+            .height()
+            .min(self.free_band(ctx).span())
+            * self.style.height_fraction;
+        let mut panel = TopBottomPanel::bottom(Id::new(WINDOW_TITLE))
+            .frame(frame)
+            .resizable(false)
+            .show_separator_line(false);
+        if self.visibility < 1. && self.docked_height > 0. {
+            // Growing panel reveals the keys gradually, pushing the content up.
+            panel = panel.exact_height(self.docked_height * self.visibility);
+        }
+        let response = panel
+            .show(ctx, |ui| self.keys_ui(ui, keys, height, away, true))
+            .response;
+        if self.visibility == 1. {
+            self.docked_height = response.rect.height();
+        }
+        self.covered_height = response.rect.height();
+        vec![response]
+    }
+
+    /// Window which the user can move and resize, see [`KeyboardPlacement::Floating`].
+    pub(crate) fn show_floating(
+        &mut self,
+        ctx: &Context,
+        keys: Vec<Row>,
+        frame: Frame,
+        away: f32,
+        needed: bool,
+    ) -> Vec<Response> {
+        let screen = ctx.screen_rect();
+        let opacity = match self.scroll_behavior {
+            ScrollBehavior::SlideAway => self.visibility * (1. - away),
+            _ => self.visibility,
+        };
+        let shown: Vec<Response> = self
+            .keyboard_window(
+                Id::new(WINDOW_TITLE),
+                frame.multiply_with_opacity(opacity),
+                away,
+                needed,
+            )
+            .resizable([true, false])
+            .constrain_to(Rect::from_x_y_ranges(screen.x_range(), self.free_band(ctx)))
+            .pivot(Align2::CENTER_BOTTOM)
+            .default_pos(screen.center_bottom())
+            .default_width(screen.width() * FLOATING_WIDTH_FRACTION)
+            .show(ctx, |ui| {
+                ui.multiply_opacity(opacity);
+                let height = ui.available_width() * FLOATING_HEIGHT_RATIO;
+                self.keys_ui(ui, keys, height, away, true);
+            })
+            .map(|response| response.response)
+            .into_iter()
+            .collect();
+        self.covered_height = shown.first().map_or(0., |response| {
+            (screen.bottom() - response.rect.top()).max(0.)
+        });
+        shown
+    }
+
+    /// Window next to a widget, see [`KeyboardPlacement::Follow`].
+    pub(crate) fn show_following(
+        &mut self,
+        ctx: &Context,
+        keys: Vec<Row>,
+        frame: Frame,
+        away: f32,
+        needed: bool,
+    ) -> Vec<Response> {
+        let target = self.followed.or_else(|| {
+            self.input_widget
+                .and_then(|id| ctx.read_response(id))
+                .map(|response| response.rect)
+        });
+        let Some(target) = target else {
+            return self.show_overlay(ctx, keys, frame, away, needed);
+        };
+
+        let screen = ctx.screen_rect();
+        let free = Rect::from_x_y_ranges(screen.x_range(), self.free_band(ctx));
+        let margin = frame.total_margin().sum();
+        let width = (screen.width() * FOLLOW_WIDTH_FRACTION)
+            .max(target.width())
+            .min(screen.width())
+            - margin.x;
+        // Size is not known until the keyboard is shown for the first time.
+        let size = self.last_rect().map_or(
+            vec2(width, width * FLOATING_HEIGHT_RATIO) + margin,
+            |rect| rect.size(),
+        );
+        let opacity = match self.scroll_behavior {
+            ScrollBehavior::SlideAway => self.visibility * (1. - away),
+            _ => self.visibility,
+        };
+        let shown = self
+            .keyboard_window(
+                Id::new(WINDOW_TITLE),
+                frame.multiply_with_opacity(opacity),
+                away,
+                needed,
+            )
+            .fixed_pos(position_next_to(free, target, size, self.handedness))
+            .fixed_size(vec2(width, 0.))
+            .constrain_to(free)
+            .show(ctx, |ui| {
+                ui.multiply_opacity(opacity);
+                self.keys_ui(ui, keys, width * FLOATING_HEIGHT_RATIO, away, true);
+            })
+            .map(|response| response.response)
+            .into_iter()
+            .collect();
+        self.covered_height = 0.;
+        shown
+    }
+
+    /// Halves of the keys anchored to the screen edges, see [`KeyboardPlacement::Split`].
+    pub(crate) fn show_split(
+        &mut self,
+        ctx: &Context,
+        keys: Vec<Row>,
+        frame: Frame,
+        away: f32,
+        needed: bool,
+    ) -> Vec<Response> {
+        let available = ctx.available_rect();
+        let band = self.free_band(ctx);
+        let width = available.width() * SPLIT_WIDTH_FRACTION - frame.total_margin().sum().x;
+        let height = available.height().min(band.span()) * self.style.height_fraction;
+        let lift = ctx.screen_rect().bottom() - band.max;
+        let offset = vec2(0., self.slide_offset(ctx, away) - lift);
+        let (left, right) = layouts::split_rows(keys);
+
+        let mut shown = Vec::new();
+        for (side, (keys, anchor)) in [(left, Align2::LEFT_BOTTOM), (right, Align2::RIGHT_BOTTOM)]
+            .into_iter()
+            .enumerate()
+        {
+            let response = self
+                .keyboard_window(Id::new(WINDOW_TITLE).with(side), frame, away, needed)
+                .anchor(anchor, offset)
+                .fixed_size(vec2(width, 0.))
+                // Suggestions are offered only once, above the left half.
+                .show(ctx, |ui| self.keys_ui(ui, keys, height, away, side == 0));
+            shown.extend(response.map(|response| response.response));
+        }
+        self.covered_height = self.slid_in_height(&shown) + lift;
+        shown
+    }
+
+    /// Window of the keyboard with given `id`, which is anchored to the screen unless
+    /// configured otherwise.
+    pub(crate) fn keyboard_window(
+        &self,
+        id: Id,
+        frame: Frame,
+        away: f32,
+        needed: bool,
+    ) -> Window<'static> {
+        Window::new(WINDOW_TITLE)
+            .id(id)
+            .frame(frame)
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(false)
+            .interactable(away == 0. && needed)
+            .constrain(away == 0. && self.visibility == 1.)
+            .order(Order::Foreground)
+    }
+
+    /// Vertical range of the screen the keyboard can take, between the avoided rects above and
+    /// below the middle of the screen.
+    fn free_band(&self, ctx: &Context) -> Rangef {
+        let screen = ctx.screen_rect();
+        let band = self.avoided.iter().fold(screen.y_range(), |band, rect| {
+            if rect.center().y > screen.center().y {
+                Rangef::new(band.min, band.max.min(rect.top()))
+            } else {
+                Rangef::new(band.min.max(rect.bottom()), band.max)
+            }
+        });
+        // Rects overlapping each other vertically leave no room at all.
+        Rangef::new(band.min.min(band.max), band.max)
+    }
+
+    /// How far the keyboard anchored to the bottom of the screen is moved below it, following
+    /// the show animation and the scrolls elsewhere.
+    fn slide_offset(&self, ctx: &Context, away: f32) -> f32 {
+        // Height is not known until the keyboard is shown for the first time, so it starts
+        // below the screen.
+        let height = self
+            .last_rect()
+            .map_or(ctx.screen_rect().height(), |rect| rect.height());
+        let mut offset = (1. - self.visibility) * height;
+        if self.scroll_behavior == ScrollBehavior::SlideAway {
+            offset += away * height;
+        }
+        offset
+    }
+
+    /// Height of the tallest of the `shown` windows, following the show animation. Sliding
+    /// away because of a scroll does not count, so the content does not move while scrolled.
+    fn slid_in_height(&self, shown: &[Response]) -> f32 {
+        let height = shown
+            .iter()
+            .map(|response| response.rect.height())
+            .fold(0., f32::max);
+        height * self.visibility
+    }
+}
+
+/// Where to put the keyboard of given `size` next to the `target`, within the `free` rect: below
+/// the target if there is room, otherwise above it, to the right or to the left of it. Mirrored
+/// for the left-handed users, aligning it to the right edge of the target and trying the left
+/// side first.
+fn position_next_to(free: Rect, target: Rect, size: Vec2, handedness: Handedness) -> Pos2 {
+    let x = match handedness {
+        Handedness::Right => target.left(),
+        Handedness::Left => target.right() - size.x,
+    };
+    let x = x.min(free.right() - size.x).max(free.left());
+    let y = target.top().min(free.bottom() - size.y).max(free.top());
+    let right = pos2(target.right(), y);
+    let left = pos2(target.left() - size.x, y);
+    let (first_side, second_side) = match handedness {
+        Handedness::Right => (right, left),
+        Handedness::Left => (left, right),
+    };
+    let candidates = [
+        pos2(x, target.bottom()),
+        pos2(x, target.top() - size.y),
+        first_side,
+        second_side,
+    ];
+    candidates
+        .into_iter()
+        .find(|pos| free.contains_rect(Rect::from_min_size(*pos, size)))
+        .unwrap_or(candidates[0])
+}
@@ -0,0 +1,121 @@
+//! Recording the events emitted by the keyboard and playing them back.
+
+use crate::Keyboard;
+use egui::{Context, Event};
+use std::collections::VecDeque;
+
+/// Event emitted by the keyboard.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct RecordedEvent {
+    /// When the event was delivered to egui, in egui time (seconds).
+    pub time: f64,
+    pub event: Event,
+}
+
+/// Events emitted by the keyboard, in the order they were delivered to egui. Useful for
+/// reproducing bug reports or scripting demos.
+///
+/// With the `serde` feature, recordings can be saved and loaded as RON.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Recording {
+    pub events: Vec<RecordedEvent>,
+}
+
+#[cfg(feature = "serde")]
+impl Recording {
+    /// Load the recording from a RON file.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, crate::LoadError> {
+        crate::load::from_file(path)
+    }
+
+    /// Parse the recording from a RON string.
+    pub fn from_ron(ron: &str) -> Result<Self, crate::LoadError> {
+        crate::load::from_ron(ron)
+    }
+
+    /// Serialize the recording as RON, e.g. to attach it to a bug report.
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+}
+
+/// Recording being played back.
+pub(crate) struct Replay {
+    events: VecDeque<RecordedEvent>,
+    /// Difference between the current time and the time of the recording.
+    offset: Option<f64>,
+}
+
+impl Keyboard {
+    /// Start recording the events delivered by [`Keyboard::pump_events`], dropping the previous
+    /// recording.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Recording::default());
+    }
+
+    /// Stop recording and return what was recorded.
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.recording.take()
+    }
+
+    /// What was recorded so far, if recording.
+    pub fn recording(&self) -> Option<&Recording> {
+        self.recording.as_ref()
+    }
+
+    /// Play the `recording` back through [`Keyboard::pump_events`], keeping the original pauses
+    /// between the events. Replaces a playback in progress.
+    pub fn replay(&mut self, recording: Recording) {
+        self.replay = Some(Replay {
+            events: recording.events.into(),
+            offset: None,
+        });
+    }
+
+    /// Whether a recording is being played back.
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Events of the playback which are due.
+    pub(crate) fn replayed_events(&mut self, ctx: &Context) -> Vec<Event> {
+        let Some(replay) = &mut self.replay else {
+            return Vec::new();
+        };
+
+        let now = ctx.input(|input| input.time);
+        let offset = *replay
+            .offset
+            .get_or_insert_with(|| replay.events.front().map_or(0., |first| now - first.time));
+
+        let mut events = Vec::new();
+        while let Some(recorded) = replay.events.front() {
+            if recorded.time + offset > now {
+                break;
+            }
+            events.extend(replay.events.pop_front().map(|recorded| recorded.event));
+        }
+
+        if replay.events.is_empty() {
+            self.replay = None;
+        } else {
+            ctx.request_repaint();
+        }
+        events
+    }
+
+    /// Remember the events which are about to be delivered, if recording.
+    pub(crate) fn record(&mut self, ctx: &Context, events: &[Event]) {
+        if let Some(recording) = &mut self.recording {
+            let time = ctx.input(|input| input.time);
+            recording
+                .events
+                .extend(events.iter().map(|event| RecordedEvent {
+                    time,
+                    event: event.clone(),
+                }));
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Keys repeating what they do while they are held, like backspace.
+
+use crate::{Key, Keyboard, REPEAT_DELAY, REPEAT_INTERVAL};
+use egui::{Id, Response, Ui};
+
+/// Key which is held long enough to repeat.
+#[derive(Clone, Copy)]
+pub(crate) struct Repeating {
+    pub(crate) key: Id,
+    /// How many times the key repeated so far.
+    count: u32,
+}
+
+impl Keyboard {
+    /// Repeat what the `key`, which got the `response`, does while it is held. Moving the pointer
+    /// away from the key, e.g. to swipe over the space key, pauses the repeating.
+    pub(crate) fn repeat_key(&mut self, ui: &Ui, response: &Response, key: &Key) {
+        let interaction = self.interaction(ui, response);
+        let held_here = self
+            .repeating
+            .filter(|repeating| repeating.key == response.id);
+        if !interaction.down {
+            if held_here.is_some() {
+                self.repeating = None;
+            }
+            return;
+        }
+        if !interaction
+            .pos
+            .is_some_and(|pos| response.rect.contains(pos))
+        {
+            return;
+        }
+
+        let (delay, interval) = self.key_repeat.unwrap_or((REPEAT_DELAY, REPEAT_INTERVAL));
+        if interaction.held < delay {
+            ui.ctx()
+                .request_repaint_after_secs((delay - interaction.held) as f32);
+            return;
+        }
+
+        let due = ((interaction.held - delay) / interval) as u32 + 1;
+        let count = held_here.map_or(0, |repeating| repeating.count);
+        if due > count {
+            if let Some(actions) = self.key_actions(key) {
+                for _ in count..due {
+                    self.perform(actions.clone());
+                }
+                self.focus_back_to_input_widget(ui.ctx());
+            }
+            self.repeating = Some(Repeating {
+                key: response.id,
+                count: due,
+            });
+        }
+        ui.ctx().request_repaint_after_secs(interval as f32);
+    }
+}
@@ -0,0 +1,455 @@
+//! Scripted flows driving the keyboard by tapping its keys, e.g. as integration tests of an
+//! application's keyboard setup.
+
+use crate::suggestions::{current_word, is_word_char};
+use crate::{FrequencyDictionary, Key, Keyboard, DEFAULT_SUGGESTION_SHORTCUTS};
+use egui::{vec2, CentralPanel, Context, Event, Id, PointerButton, Pos2, RawInput, Rect, TextEdit};
+
+/// Size of the screen the scenarios run on, in points.
+const SCREEN_SIZE: egui::Vec2 = vec2(400.0, 800.0);
+
+/// Time between the frames, in seconds.
+const FRAME_TIME: f64 = 1.0 / 60.0;
+
+/// How many frames the keyboard gets to show up for a focused field.
+const MAX_SHOW_FRAMES: usize = 120;
+
+/// Scripted flow of focusing text fields, tapping the keys and checking what ends up in the
+/// fields. Each run shows the fields in a fresh egui context with the keyboard under them, taps
+/// its keys like a finger would and fails at the first step which does not go as scripted.
+///
+/// Like a spell checker of an application, the fields report their last finished word as
+/// misspelled when it is not [`Keyboard::is_word_known`], so the keyboard offers its
+/// corrections in front of the other suggestions.
+///
+/// # Examples
+///
+/// ```
+/// use egui_keyboard::{FrequencyDictionary, Keyboard, Scenario};
+///
+/// let mut keyboard = Keyboard::default()
+///     .suggestion_provider(FrequencyDictionary::new([("hello", 10)]));
+/// Scenario::new("greeting")
+///     .focus(0)
+///     .type_text("Hel")
+///     .accept_suggestion(0)
+///     .type_text("World!")
+///     .expect_text(0, "Hello World!")
+///     .run(&mut keyboard)
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    name: String,
+    steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug)]
+enum Step {
+    Focus(usize),
+    Type(String),
+    Tap(String),
+    AcceptSuggestion(usize),
+    Expect { field: usize, text: String },
+}
+
+/// Step of a [`Scenario`] which did not go as scripted.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScenarioError {
+    /// The keyboard did not show up for the focused field.
+    NotShown { scenario: String },
+    /// No key types the character, on any layer reachable by the shift and the `!#1` keys.
+    NoKeyFor { scenario: String, character: char },
+    /// No key has the label.
+    NoKeyLabeled { scenario: String, label: String },
+    /// The application's suggestion shortcuts have no shortcut for the suggestion.
+    NoShortcutFor { scenario: String, suggestion: usize },
+    UnexpectedText {
+        scenario: String,
+        field: usize,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl std::fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotShown { scenario } => {
+                write!(
+                    f,
+                    "{scenario}: the keyboard is not shown for the focused field"
+                )
+            }
+            Self::NoKeyFor {
+                scenario,
+                character,
+            } => {
+                write!(f, "{scenario}: no key types {character:?}")
+            }
+            Self::NoKeyLabeled { scenario, label } => {
+                write!(f, "{scenario}: no key is labeled {label:?}")
+            }
+            Self::NoShortcutFor {
+                scenario,
+                suggestion,
+            } => {
+                write!(
+                    f,
+                    "{scenario}: no shortcut accepts the suggestion {suggestion}"
+                )
+            }
+            Self::UnexpectedText {
+                scenario,
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{scenario}: field {field} contains {actual:?} instead of {expected:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl Scenario {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Focus the text field with the `index`, shown below the previous ones, and wait for the
+    /// keyboard to show up.
+    pub fn focus(mut self, index: usize) -> Self {
+        self.steps.push(Step::Focus(index));
+        self
+    }
+
+    /// Tap the keys typing the characters of the `text`, switching the layers with the shift
+    /// and the `!#1` keys when the current one does not have them.
+    pub fn type_text(mut self, text: impl Into<String>) -> Self {
+        self.steps.push(Step::Type(text.into()));
+        self
+    }
+
+    /// Tap the key with the `label`, e.g. `!#1` to switch to the symbols.
+    pub fn tap(mut self, label: impl Into<String>) -> Self {
+        self.steps.push(Step::Tap(label.into()));
+        self
+    }
+
+    /// Accept the suggestion with the `index` in the suggestion bar, the completion and the
+    /// corrections first, with its [`Keyboard::suggestion_shortcuts`].
+    pub fn accept_suggestion(mut self, index: usize) -> Self {
+        self.steps.push(Step::AcceptSuggestion(index));
+        self
+    }
+
+    /// Check that the field with the `index` contains the `text`.
+    pub fn expect_text(mut self, index: usize, text: impl Into<String>) -> Self {
+        self.steps.push(Step::Expect {
+            field: index,
+            text: text.into(),
+        });
+        self
+    }
+
+    /// Run the scenario with the `keyboard`, reset first.
+    pub fn run(&self, keyboard: &mut Keyboard) -> Result<(), ScenarioError> {
+        keyboard.reset();
+        let fields = self
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                Step::Focus(field) | Step::Expect { field, .. } => Some(field + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        let mut run = Run {
+            keyboard,
+            ctx: Context::default(),
+            fields: vec![String::new(); fields],
+            focus: None,
+            time: 0.0,
+        };
+        for step in &self.steps {
+            run.step(step)
+                .map_err(|error| error.in_scenario(&self.name))?;
+        }
+        Ok(())
+    }
+
+    /// Scenarios of the common flows, for a keyboard with the [`Scenario::demo_dictionary`] as
+    /// its suggestion provider.
+    ///
+    /// ```
+    /// use egui_keyboard::{Keyboard, Scenario};
+    ///
+    /// let mut keyboard = Keyboard::default().suggestion_provider(Scenario::demo_dictionary());
+    /// for scenario in Scenario::demos() {
+    ///     scenario.run(&mut keyboard).unwrap();
+    /// }
+    /// ```
+    pub fn demos() -> Vec<Scenario> {
+        vec![
+            Scenario::new("typing")
+                .focus(0)
+                .type_text("hello world")
+                .expect_text(0, "hello world"),
+            Scenario::new("layers")
+                .focus(0)
+                .type_text("Hi, it's 5 o'clock!")
+                .expect_text(0, "Hi, it's 5 o'clock!"),
+            Scenario::new("switching fields")
+                .focus(0)
+                .type_text("user")
+                .focus(1)
+                .type_text("secret")
+                .expect_text(0, "user")
+                .expect_text(1, "secret"),
+            Scenario::new("suggestions")
+                .focus(0)
+                .type_text("keyb")
+                .accept_suggestion(0)
+                .type_text("works")
+                .expect_text(0, "keyboard works"),
+            Scenario::new("autocorrect")
+                .focus(0)
+                .type_text("hello wrold ")
+                .accept_suggestion(0)
+                .type_text("again")
+                .expect_text(0, "hello world again"),
+        ]
+    }
+
+    /// Words the [`Scenario::demos`] rely on.
+    pub fn demo_dictionary() -> FrequencyDictionary {
+        FrequencyDictionary::new([("keyboard", 10), ("hello", 5), ("world", 5)])
+    }
+}
+
+/// Failure of a step, before it is attributed to a scenario.
+enum StepError {
+    NotShown,
+    NoKeyFor(char),
+    NoKeyLabeled(String),
+    NoShortcutFor(usize),
+    UnexpectedText {
+        field: usize,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl StepError {
+    fn in_scenario(self, name: &str) -> ScenarioError {
+        let scenario = name.to_owned();
+        match self {
+            Self::NotShown => ScenarioError::NotShown { scenario },
+            Self::NoKeyFor(character) => ScenarioError::NoKeyFor {
+                scenario,
+                character,
+            },
+            Self::NoKeyLabeled(label) => ScenarioError::NoKeyLabeled { scenario, label },
+            Self::NoShortcutFor(suggestion) => ScenarioError::NoShortcutFor {
+                scenario,
+                suggestion,
+            },
+            Self::UnexpectedText {
+                field,
+                expected,
+                actual,
+            } => ScenarioError::UnexpectedText {
+                scenario,
+                field,
+                expected,
+                actual,
+            },
+        }
+    }
+}
+
+/// Scenario being run.
+struct Run<'a> {
+    keyboard: &'a mut Keyboard,
+    ctx: Context,
+    /// Texts of the fields.
+    fields: Vec<String>,
+    /// Field to focus in the next frame.
+    focus: Option<usize>,
+    time: f64,
+}
+
+impl Run<'_> {
+    fn step(&mut self, step: &Step) -> Result<(), StepError> {
+        match step {
+            Step::Focus(field) => {
+                self.focus = Some(*field);
+                self.frame(Vec::new());
+                for _ in 0..MAX_SHOW_FRAMES {
+                    if self.keyboard.visibility >= 1.0 {
+                        self.settle();
+                        return Ok(());
+                    }
+                    self.frame(Vec::new());
+                }
+                Err(StepError::NotShown)
+            }
+            Step::Type(text) => text.chars().try_for_each(|c| self.type_char(c)),
+            Step::Tap(label) => {
+                let pos = self
+                    .key_pos(|keyboard, key| keyboard.key_label(key, 0) == *label)
+                    .ok_or_else(|| StepError::NoKeyLabeled(label.clone()))?;
+                self.tap(pos);
+                Ok(())
+            }
+            Step::AcceptSuggestion(index) => {
+                let shortcut = self
+                    .keyboard
+                    .suggestion_shortcuts
+                    .as_deref()
+                    .unwrap_or(&DEFAULT_SUGGESTION_SHORTCUTS)
+                    .get(*index)
+                    .copied()
+                    .ok_or(StepError::NoShortcutFor(*index))?;
+                self.frame(vec![Event::Key {
+                    key: shortcut.logical_key,
+                    physical_key: None,
+                    pressed: true,
+                    repeat: false,
+                    modifiers: shortcut.modifiers,
+                }]);
+                self.settle();
+                Ok(())
+            }
+            Step::Expect { field, text } => {
+                // Deliver the events still waiting.
+                self.frame(Vec::new());
+                let actual = &self.fields[*field];
+                if actual == text {
+                    Ok(())
+                } else {
+                    Err(StepError::UnexpectedText {
+                        field: *field,
+                        expected: text.clone(),
+                        actual: actual.clone(),
+                    })
+                }
+            }
+        }
+    }
+
+    /// Tap the key typing `c`, switching the layers until one has it.
+    fn type_char(&mut self, c: char) -> Result<(), StepError> {
+        let text = c.to_string();
+        let types_char = |keyboard: &Keyboard, key: &Key| match key.base() {
+            Key::Space => c == ' ',
+            Key::Text(_) => keyboard.key_label(key, 0) == text,
+            _ => false,
+        };
+        // The symbols first, then back to the letters and to the other case.
+        let switches: [fn(&Key) -> bool; 3] = [
+            |key| matches!(key.base(), Key::Special),
+            |key| matches!(key.base(), Key::Special),
+            |key| matches!(key.base(), Key::Upper),
+        ];
+        for switch in switches {
+            if let Some(pos) = self.key_pos(types_char) {
+                self.tap(pos);
+                return Ok(());
+            }
+            if let Some(pos) = self.key_pos(|_, key| switch(key)) {
+                self.tap(pos);
+            }
+        }
+        match self.key_pos(types_char) {
+            Some(pos) => {
+                self.tap(pos);
+                Ok(())
+            }
+            None => Err(StepError::NoKeyFor(c)),
+        }
+    }
+
+    /// Center of the first key shown in the last frame for which `matches` holds.
+    fn key_pos(&self, matches: impl Fn(&Keyboard, &Key) -> bool) -> Option<Pos2> {
+        self.keyboard
+            .key_rects
+            .iter()
+            .find(|(_, key)| matches(self.keyboard, key))
+            .map(|(rect, _)| rect.center())
+    }
+
+    /// Press at `pos` in one frame and release in the next one.
+    fn tap(&mut self, pos: Pos2) {
+        let button = |pressed| Event::PointerButton {
+            pos,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: Default::default(),
+        };
+        self.frame(vec![Event::PointerMoved(pos), button(true)]);
+        self.frame(vec![button(false)]);
+        self.frame(vec![Event::PointerGone]);
+        self.settle();
+    }
+
+    /// Run frames until the keys stop moving, e.g. when the suggestion bar appears above them.
+    fn settle(&mut self) {
+        let rects = |keyboard: &Keyboard| -> Vec<Rect> {
+            keyboard.key_rects.iter().map(|(rect, _)| *rect).collect()
+        };
+        for _ in 0..MAX_SHOW_FRAMES {
+            let before = rects(self.keyboard);
+            self.frame(Vec::new());
+            if rects(self.keyboard) == before {
+                return;
+            }
+        }
+    }
+
+    /// Run a frame of the fields with the keyboard, with the `events` as the input.
+    fn frame(&mut self, events: Vec<Event>) {
+        self.time += FRAME_TIME;
+        let mut input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, SCREEN_SIZE)),
+            time: Some(self.time),
+            events,
+            ..Default::default()
+        };
+        self.keyboard.filter_input(&mut input);
+        let focus = self.focus.take();
+        let (keyboard, fields) = (&mut *self.keyboard, &mut self.fields);
+        let _ = self.ctx.run(input, |ctx| {
+            keyboard.pump_events(ctx);
+            CentralPanel::default().show(ctx, |ui| {
+                for (i, text) in fields.iter_mut().enumerate() {
+                    let id = Id::new(("scenario_field", i));
+                    let response = ui.add(TextEdit::singleline(text).id(id));
+                    if focus == Some(i) {
+                        response.request_focus();
+                    }
+                    if response.has_focus() {
+                        let word = last_finished_word(text);
+                        if !keyboard.is_word_known(word) {
+                            keyboard.set_misspelled(id, word);
+                        }
+                    }
+                }
+            });
+            keyboard.show(ctx);
+        });
+    }
+}
+
+/// Last word of the `text` followed by something else than a word, e.g. a space.
+fn last_finished_word(text: &str) -> &str {
+    let finished =
+        text[..text.len() - current_word(text).len()].trim_end_matches(|c| !is_word_char(c));
+    current_word(finished)
+}
@@ -0,0 +1,40 @@
+//! Parts of the application the keyboard types into, see [`Keyboard::scope`].
+
+use crate::Keyboard;
+use egui::{Context, Id};
+
+impl Keyboard {
+    /// Only show the keyboard for the widgets in the layer with the `id`, e.g. the `Window` or
+    /// the `Area` of one tool of a larger application, ignoring the focus elsewhere. Call it
+    /// again to add more layers. The id of a window is the one it is created with, the panels
+    /// share the layer of `egui::LayerId::background()`.
+    ///
+    /// ```
+    /// let keyboard = egui_keyboard::Keyboard::default().scope(egui::Id::new("Settings"));
+    /// # let ctx = egui::Context::default();
+    /// # let _ = ctx.run(Default::default(), |ctx| {
+    /// egui::Window::new("Settings").show(ctx, |ui| {
+    ///     // The keyboard types into the widgets here, but not into the ones in other windows.
+    /// });
+    /// # });
+    /// ```
+    pub fn scope(mut self, id: Id) -> Self {
+        self.scopes.push(id);
+        self
+    }
+
+    /// Whether the keyboard serves the `focused` widget, see [`Keyboard::scope`]. Widgets shown
+    /// for the first time are not known to be in any scope until the next frame.
+    pub(crate) fn in_scope(&self, ctx: &Context, focused: Id) -> bool {
+        self.scopes.is_empty()
+            || ctx
+                .read_response(focused)
+                .is_some_and(|response| self.scopes.contains(&response.layer_id.id))
+    }
+
+    /// Whether the focus is not on a widget outside of the [`Keyboard::scope`].
+    pub(crate) fn focus_in_scope(&self, ctx: &Context) -> bool {
+        ctx.memory(|memory| memory.focused())
+            .is_none_or(|focused| self.in_scope(ctx, focused))
+    }
+}
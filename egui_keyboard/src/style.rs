@@ -0,0 +1,279 @@
+//! Look of the keyboard.
+
+use crate::layouts::Layer;
+use crate::SPACE_BETWEEN_KEYS;
+use egui::{Color32, Painter, Rect, Stroke, StrokeKind, TextStyle, Vec2};
+use std::collections::HashMap;
+
+/// Look of the keyboard. Whatever is not set follows the current egui style.
+///
+/// With the `serde` feature, the style can be loaded from a [RON](https://github.com/ron-rs/ron)
+/// file, so the keyboard can be restyled without recompiling. Fields which are left out keep
+/// their defaults, colors are given as premultiplied RGBA:
+///
+/// ```ron
+/// (
+///     background: (20, 20, 30, 255),
+///     key_fill: (60, 60, 90, 255),
+///     key_fill_pressed: (90, 90, 140, 255),
+///     key_corner_radius: 8.0,
+///     font_size: 24.0,
+///     height_fraction: 0.4,
+///     key_shapes: {
+///         Space: Pill,
+///     },
+///     key_colors: {
+///         Action: (fill: (40, 40, 60, 255)),
+///         Modifier: (fill: (40, 40, 60, 255)),
+///     },
+/// )
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct KeyboardStyle {
+    /// Fill of the keyboard background. `extreme_bg_color` if not set. Ignored when
+    /// [`crate::Keyboard::frame`] is set.
+    pub background: Option<Color32>,
+
+    /// Fill of the keys.
+    pub key_fill: Option<Color32>,
+
+    /// Fill of the key under the pointer.
+    pub key_fill_hovered: Option<Color32>,
+
+    /// Fill of the key which is being pressed.
+    pub key_fill_pressed: Option<Color32>,
+
+    /// Color of the key labels.
+    pub key_text: Option<Color32>,
+
+    /// Colors of the keys of some categories, overriding the colors above, e.g. to make the
+    /// action and the modifier keys stand out from the letters.
+    pub key_colors: HashMap<KeyCategory, KeyColors>,
+
+    /// Text style of the key labels. `Heading` if not set.
+    pub text_style: Option<TextStyle>,
+
+    /// Size of the key labels, before [`crate::Keyboard::accessibility_scale`] is applied.
+    /// The size of the text style if not set.
+    pub font_size: Option<f32>,
+
+    /// Space between the keys, relative to the size of a regular key.
+    pub key_spacing: f32,
+
+    /// Part of the screen height taken by the rows of keys, before
+    /// [`crate::Keyboard::accessibility_scale`] is applied.
+    pub height_fraction: f32,
+
+    /// Smallest size of the buttons which are not part of the layout, like the clipboard chip.
+    pub min_key_size: Vec2,
+
+    /// Rounding of the key corners.
+    pub key_corner_radius: Option<f32>,
+
+    /// Shapes of the keys, drawn by the keyboard instead of the egui button frame. Categories
+    /// which are not listed look like regular egui buttons.
+    pub key_shapes: HashMap<KeyCategory, KeyShape>,
+
+    /// Colors laid over the style while a layer is shown, e.g. to tell the emojis from the
+    /// letters at a glance. Layouts can bring their own, see
+    /// [`crate::layouts::KeyboardLayoutBuilder::style`].
+    pub layer_styles: HashMap<Layer, LayerStyle>,
+
+    /// Whether the keys offering alternates by a long press show the first of them small in
+    /// their corner, e.g. an accented letter or a digit of a custom layout. Turn it off for
+    /// a cleaner look.
+    pub alternate_hints: bool,
+}
+
+/// Kind of a key, so different kinds can be styled differently.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum KeyCategory {
+    /// Key typing a character.
+    Character,
+    Space,
+    /// Key doing something else than typing, e.g. backspace or moving the caret.
+    Action,
+    /// Key switching the layer or the page, e.g. shift.
+    Modifier,
+}
+
+/// Colors of the keys of a [`KeyCategory`], see [`KeyboardStyle::key_colors`]. Whatever is not
+/// set follows the colors of all the keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct KeyColors {
+    pub fill: Option<Color32>,
+    pub fill_hovered: Option<Color32>,
+    pub fill_pressed: Option<Color32>,
+    pub text: Option<Color32>,
+}
+
+impl KeyColors {
+    /// These colors with whatever is not set taken from the `other` ones.
+    pub(crate) fn or(self, other: Self) -> Self {
+        Self {
+            fill: self.fill.or(other.fill),
+            fill_hovered: self.fill_hovered.or(other.fill_hovered),
+            fill_pressed: self.fill_pressed.or(other.fill_pressed),
+            text: self.text.or(other.text),
+        }
+    }
+}
+
+/// Colors laid over the [`KeyboardStyle`] while a layer is shown, see
+/// [`KeyboardStyle::layer_styles`]. Whatever is not set keeps the colors of the style.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(default)
+)]
+pub struct LayerStyle {
+    /// Fill of the keyboard background.
+    pub background: Option<Color32>,
+    /// Colors of all the keys, over the colors of their categories.
+    pub keys: KeyColors,
+    /// Fill of the selected keys, e.g. the locked shift.
+    pub accent: Option<Color32>,
+}
+
+impl LayerStyle {
+    /// This style with whatever is not set taken from the `other` one.
+    pub(crate) fn or(self, other: Self) -> Self {
+        Self {
+            background: self.background.or(other.background),
+            keys: self.keys.or(other.keys),
+            accent: self.accent.or(other.accent),
+        }
+    }
+}
+
+/// Shape of a key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum KeyShape {
+    Rounded {
+        corner_radius: f32,
+    },
+    /// Circle fitting into the key, e.g. for a PIN pad.
+    Circle,
+    /// Rectangle with fully rounded ends.
+    Pill,
+}
+
+impl KeyShape {
+    pub(crate) fn paint(self, painter: &Painter, rect: Rect, fill: Color32, stroke: Stroke) {
+        match self {
+            Self::Rounded { corner_radius } => {
+                painter.rect(rect, corner_radius, fill, stroke, StrokeKind::Inside);
+            }
+            Self::Circle => {
+                let radius = rect.width().min(rect.height()) / 2.;
+                painter.circle(rect.center(), radius - stroke.width / 2., fill, stroke);
+            }
+            Self::Pill => {
+                let radius = rect.width().min(rect.height()) / 2.;
+                painter.rect(rect, radius, fill, stroke, StrokeKind::Inside);
+            }
+        }
+    }
+}
+
+/// Part of the screen height taken by the keys, unless set with
+/// [`KeyboardStyle::height_fraction`].
+const KEYBOARD_HEIGHT_FRACTION: f32 = 1.0 / 3.0;
+
+/// Smallest size of the buttons outside of the layout, unless set with
+/// [`KeyboardStyle::min_key_size`].
+const MIN_KEY_SIZE: Vec2 = Vec2::new(10.0, 50.0);
+
+impl KeyboardStyle {
+    /// White labels on black keys, with the action and the modifier keys labeled in yellow, e.g.
+    /// for a kiosk in a bright place or for users with low vision.
+    pub fn high_contrast() -> Self {
+        let highlighted = KeyColors {
+            text: Some(Color32::YELLOW),
+            ..Default::default()
+        };
+        Self {
+            background: Some(Color32::BLACK),
+            key_fill: Some(Color32::BLACK),
+            key_fill_hovered: Some(Color32::from_gray(60)),
+            key_fill_pressed: Some(Color32::from_rgb(0, 0, 200)),
+            key_text: Some(Color32::WHITE),
+            key_colors: HashMap::from([
+                (KeyCategory::Action, highlighted),
+                (KeyCategory::Modifier, highlighted),
+            ]),
+            ..Default::default()
+        }
+    }
+
+    /// Text style of the key labels.
+    pub(crate) fn text_style(&self) -> TextStyle {
+        self.text_style.clone().unwrap_or(TextStyle::Heading)
+    }
+
+    /// Colors of the keys of the `category`, falling back to the colors of all the keys.
+    pub(crate) fn key_colors(&self, category: KeyCategory) -> KeyColors {
+        let colors = self.key_colors.get(&category).copied().unwrap_or_default();
+        KeyColors {
+            fill: colors.fill.or(self.key_fill),
+            fill_hovered: colors.fill_hovered.or(self.key_fill_hovered),
+            fill_pressed: colors.fill_pressed.or(self.key_fill_pressed),
+            text: colors.text.or(self.key_text),
+        }
+    }
+}
+
+impl Default for KeyboardStyle {
+    fn default() -> Self {
+        Self {
+            background: None,
+            key_fill: None,
+            key_fill_hovered: None,
+            key_fill_pressed: None,
+            key_text: None,
+            key_colors: HashMap::new(),
+            text_style: None,
+            font_size: None,
+            key_spacing: SPACE_BETWEEN_KEYS,
+            height_fraction: KEYBOARD_HEIGHT_FRACTION,
+            min_key_size: MIN_KEY_SIZE,
+            key_corner_radius: None,
+            key_shapes: HashMap::new(),
+            layer_styles: HashMap::new(),
+            alternate_hints: true,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl KeyboardStyle {
+    /// Load the style from a RON file.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, crate::LoadError> {
+        crate::load::from_file(path)
+    }
+
+    /// Parse the style from a RON string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let style = egui_keyboard::KeyboardStyle::from_ron("(key_corner_radius: 8.0)").unwrap();
+    /// assert_eq!(style.key_corner_radius, Some(8.0));
+    /// ```
+    pub fn from_ron(ron: &str) -> Result<Self, crate::LoadError> {
+        crate::load::from_ron(ron)
+    }
+}
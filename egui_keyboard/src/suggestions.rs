@@ -0,0 +1,415 @@
+//! Words offered in the suggestion bar above the keys.
+
+use crate::background::Lookup;
+use crate::{key_event, InputHint, Keyboard};
+use egui::{Context, Event, Id, Modifiers};
+use std::collections::HashMap;
+
+/// How many words are offered at once.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// How many edits away from a misspelled word the corrections can be, for words longer than
+/// [`SHORT_WORD_LENGTH`] and for the shorter ones.
+const MAX_CORRECTION_DISTANCE: (usize, usize) = (2, 1);
+
+const SHORT_WORD_LENGTH: usize = 4;
+
+/// How much of the typed text is remembered for the suggestions, in characters.
+const TYPED_TEXT_LIMIT: usize = 256;
+
+/// How many of the last words of the typed text the suggestions are looked up with in the
+/// background, see [`recent_text`].
+const RECENT_WORDS: usize = 5;
+
+/// Source of the words offered in the suggestion bar above the keys, see
+/// [`Keyboard::suggestion_provider`].
+pub trait SuggestionProvider {
+    /// Words which could replace the last, partially typed word of the `text`, best first. The
+    /// `text` is what was typed into the focused widget through the keyboard. It starts over
+    /// when another widget gets the focus or the caret is moved with the keys.
+    fn suggest(&self, text: &str) -> Vec<String>;
+
+    /// Whether the `word` is spelled correctly, see [`Keyboard::is_word_known`]. Providers
+    /// without a dictionary know all the words.
+    fn is_word_known(&self, word: &str) -> bool {
+        let _ = word;
+        true
+    }
+
+    /// Words which the misspelled `word` was likely meant to be, best first, see
+    /// [`Keyboard::set_misspelled`].
+    fn corrections(&self, word: &str) -> Vec<String> {
+        let _ = word;
+        Vec::new()
+    }
+
+    /// Name of the language the `text` is being typed in, if the provider tells the languages
+    /// apart, like [`crate::Languages`] does, see [`Keyboard::language_on_space`].
+    fn detect_language(&self, text: &str) -> Option<String> {
+        let _ = text;
+        None
+    }
+}
+
+/// Suggests the most frequent words starting with the partially typed one, keeping its
+/// capitalization. Misspelled words are corrected to the closest words, the most frequent first.
+///
+/// # Examples
+///
+/// ```
+/// use egui_keyboard::{FrequencyDictionary, SuggestionProvider};
+///
+/// let dictionary = FrequencyDictionary::new([("hello", 10), ("help", 20), ("world", 5)]);
+/// assert_eq!(dictionary.suggest("say Hel"), ["Help", "Hello"]);
+/// assert!(dictionary.suggest("say ").is_empty());
+/// assert!(dictionary.is_word_known("World"));
+/// assert_eq!(dictionary.corrections("Wrold"), ["World"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FrequencyDictionary {
+    /// Words with how often they are used, the most frequent first.
+    words: Vec<(String, u32)>,
+}
+
+impl FrequencyDictionary {
+    /// Dictionary of the `words` with how often they are used.
+    pub fn new(words: impl IntoIterator<Item = (impl Into<String>, u32)>) -> Self {
+        let mut words: Vec<(String, u32)> = words
+            .into_iter()
+            .map(|(word, count)| (word.into(), count))
+            .collect();
+        words.sort_by(|(_, a), (_, b)| b.cmp(a));
+        Self { words }
+    }
+
+    /// Dictionary of the words in the `text`, e.g. documents of the domain of the application,
+    /// counting how often they appear.
+    pub fn from_text(text: &str) -> Self {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for word in text.split(|c: char| !is_word_char(c)) {
+            if !word.is_empty() {
+                *counts.entry(word.to_lowercase()).or_default() += 1;
+            }
+        }
+        Self::new(counts)
+    }
+}
+
+impl SuggestionProvider for FrequencyDictionary {
+    fn suggest(&self, text: &str) -> Vec<String> {
+        let word = current_word(text);
+        if word.is_empty() {
+            return Vec::new();
+        }
+        let lowercase = word.to_lowercase();
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        self.words
+            .iter()
+            .map(|(candidate, _)| candidate)
+            .filter(|candidate| {
+                let candidate = candidate.to_lowercase();
+                candidate.starts_with(&lowercase) && candidate != lowercase
+            })
+            .take(MAX_SUGGESTIONS)
+            .map(|candidate| match capitalized {
+                true => capitalize(candidate),
+                false => candidate.clone(),
+            })
+            .collect()
+    }
+
+    fn is_word_known(&self, word: &str) -> bool {
+        let lowercase = word.to_lowercase();
+        self.words
+            .iter()
+            .any(|(candidate, _)| candidate.to_lowercase() == lowercase)
+    }
+
+    fn corrections(&self, word: &str) -> Vec<String> {
+        let lowercase = word.to_lowercase();
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        let max_distance = match word.chars().count() {
+            length if length > SHORT_WORD_LENGTH => MAX_CORRECTION_DISTANCE.0,
+            _ => MAX_CORRECTION_DISTANCE.1,
+        };
+        let mut corrections: Vec<(usize, &String)> = self
+            .words
+            .iter()
+            .map(|(candidate, _)| {
+                (
+                    edit_distance(&candidate.to_lowercase(), &lowercase),
+                    candidate,
+                )
+            })
+            .filter(|(distance, _)| (1..=max_distance).contains(distance))
+            .collect();
+        // The words are sorted by frequency, which the stable sort keeps for equal distances.
+        corrections.sort_by_key(|(distance, _)| *distance);
+        corrections
+            .into_iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(_, candidate)| match capitalized {
+                true => capitalize(candidate),
+                false => candidate.clone(),
+            })
+            .collect()
+    }
+}
+
+/// How many characters need to be inserted, deleted, replaced or swapped with their neighbour
+/// to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Distances between the prefixes of `a` and `b`, row by row for the growing prefixes of `a`.
+    let mut before_previous: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let replace = previous[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            current[j] = replace.min(previous[j] + 1).min(current[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+        }
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
+}
+
+pub(crate) fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '\'' || c == '-'
+}
+
+/// Last, possibly partially typed word of the `text`.
+pub(crate) fn current_word(text: &str) -> &str {
+    let start = text
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word_char(*c))
+        .last()
+        .map_or(text.len(), |(i, _)| i);
+    &text[start..]
+}
+
+/// End of the `text` with its last [`RECENT_WORDS`] words, the partially typed one included.
+fn recent_text(text: &str) -> &str {
+    let mut words = 0;
+    let mut in_word = false;
+    for (i, c) in text.char_indices().rev() {
+        if is_word_char(c) && !in_word {
+            words += 1;
+            if words > RECENT_WORDS {
+                return &text[i + c.len_utf8()..];
+            }
+        }
+        in_word = is_word_char(c);
+    }
+    text
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+impl Keyboard {
+    /// Offer the words from the `provider` in a bar above the keys, next to the clipboard.
+    /// Tapping a word replaces the partially typed one with it, followed by a space. The
+    /// [`FrequencyDictionary`] is a simple provider to start with.
+    pub fn suggestion_provider(mut self, provider: impl SuggestionProvider + 'static) -> Self {
+        self.suggestion_provider = Some(Box::new(provider));
+        self.background_lookups = None;
+        self
+    }
+
+    /// Whether the `word` is spelled correctly according to the dictionary of the
+    /// [`Keyboard::suggestion_provider`], e.g. to underline the misspelled words of a text field
+    /// consistently with the corrections offered by the keyboard. All words are known without a
+    /// provider.
+    pub fn is_word_known(&self, word: &str) -> bool {
+        self.suggestion_provider
+            .as_ref()
+            .is_none_or(|provider| provider.is_word_known(word))
+    }
+
+    /// Report the `word` typed into the widget with given `id` as misspelled, e.g. by the spell
+    /// checker of the application. If the widget is focused and the word was typed through the
+    /// keyboard, the suggestion bar offers its corrections from the
+    /// [`Keyboard::suggestion_provider`] in front of the other words. Tapping one replaces the
+    /// misspelled word with it.
+    ///
+    /// The word is only reported for the current frame, so this needs to be called every frame
+    /// before [`Keyboard::show`].
+    pub fn set_misspelled(&mut self, id: Id, word: impl Into<String>) {
+        self.misspelled = Some((id, word.into()));
+    }
+
+    /// Where the reported misspelled word starts in the typed text, if it was typed through the
+    /// keyboard into the focused widget.
+    fn misspelled_start(&self) -> Option<(usize, &str)> {
+        let (id, word) = self.misspelled.as_ref()?;
+        if Some(*id) != self.input_widget || word.is_empty() {
+            return None;
+        }
+        let start = self.typed.rfind(word.as_str())?;
+        let end = start + word.len();
+        let whole_word = !self.typed[..start].ends_with(is_word_char)
+            && !self.typed[end..].starts_with(is_word_char);
+        whole_word.then_some((start, word.as_str()))
+    }
+
+    /// Corrections offered for the reported misspelled word.
+    pub(crate) fn corrections(&self) -> Vec<String> {
+        let (Some(provider), Some((_, word))) =
+            (&self.suggestion_provider, self.misspelled_start())
+        else {
+            return Vec::new();
+        };
+        if self.focused_input_hint() == InputHint::Password {
+            return Vec::new();
+        }
+        let mut corrections = match &self.background_lookups {
+            Some(lookups) => lookups.get(&Lookup::Corrections(word.to_owned())),
+            None => provider.corrections(word),
+        };
+        corrections.retain(|correction| !self.is_word_blocked(correction));
+        corrections.truncate(MAX_SUGGESTIONS);
+        corrections
+    }
+
+    /// Replace the reported misspelled word with the `correction`, keeping what was typed after
+    /// it. The backspace right after puts the misspelled word back.
+    pub(crate) fn accept_correction(&mut self, ctx: &Context, correction: &str) {
+        let Some((start, word)) = self.misspelled_start() else {
+            return;
+        };
+        let after = self.typed[start + word.len()..].to_owned();
+        let replaced = self.typed[start..].to_owned();
+        let corrected = format!("{correction}{after}");
+        self.replace_typed(&replaced, corrected.clone());
+        self.correction_undo = Some((corrected, replaced));
+        self.misspelled = None;
+        self.focus_back_to_input_widget(ctx);
+    }
+
+    /// Put back the text replaced by the correction accepted right before, instead of deleting
+    /// a character. Returns whether there was one.
+    pub(crate) fn undo_correction(&mut self) -> bool {
+        let Some((corrected, replaced)) = self.correction_undo.take() else {
+            return false;
+        };
+        self.replace_typed(&corrected, replaced);
+        true
+    }
+
+    /// Delete the `old` text before the caret and type the `new` one instead.
+    fn replace_typed(&mut self, old: &str, new: String) {
+        let backspace = key_event(egui::Key::Backspace, Modifiers::NONE);
+        self.push_events(
+            std::iter::repeat_n(backspace, old.chars().count()).chain([Event::Text(new)]),
+        );
+    }
+
+    /// Words offered for the text typed into the focused widget.
+    pub(crate) fn word_suggestions(&self) -> Vec<String> {
+        let Some(provider) = &self.suggestion_provider else {
+            return Vec::new();
+        };
+        if self.focused_input_hint() == InputHint::Password {
+            return Vec::new();
+        }
+        let mut words = match &self.background_lookups {
+            Some(lookups) => lookups.get(&Lookup::Suggestions(recent_text(&self.typed).to_owned())),
+            None => provider.suggest(&self.typed),
+        };
+        words.retain(|word| !self.is_word_blocked(word));
+        words.truncate(MAX_SUGGESTIONS);
+        words
+    }
+
+    /// Lookups of the words offered in this frame, see
+    /// [`Keyboard::background_suggestion_provider`].
+    pub(crate) fn wanted_lookups(&self) -> Vec<Lookup> {
+        if self.focused_input_hint() == InputHint::Password {
+            return Vec::new();
+        }
+        let mut lookups = Vec::new();
+        if !current_word(&self.typed).is_empty() {
+            lookups.push(Lookup::Suggestions(recent_text(&self.typed).to_owned()));
+        }
+        if let Some((_, word)) = self.misspelled_start() {
+            lookups.push(Lookup::Corrections(word.to_owned()));
+        }
+        lookups
+    }
+
+    /// Replace the partially typed word with the `suggestion`.
+    pub(crate) fn accept_suggestion(&mut self, ctx: &Context, suggestion: &str) {
+        let backspaces = current_word(&self.typed).chars().count();
+        let backspace = key_event(egui::Key::Backspace, Modifiers::NONE);
+        self.push_events(
+            std::iter::repeat_n(backspace, backspaces)
+                .chain([Event::Text(format!("{suggestion} "))]),
+        );
+        self.focus_back_to_input_widget(ctx);
+    }
+
+    /// Follow the text typed through the keyboard, for the suggestions. Moving the caret makes
+    /// the text unreliable, so it starts over, after reporting the word or, with Enter, the
+    /// field to the [`Keyboard::autofill_store`].
+    pub(crate) fn track_typed(&mut self, event: &Event) {
+        // Only the backspace right after the correction undoes it.
+        self.correction_undo = None;
+        if self.focused_input_hint() == InputHint::Password {
+            self.typed.clear();
+            return;
+        }
+        match event {
+            Event::Text(text) => {
+                for c in text.chars() {
+                    if !is_word_char(c) {
+                        self.commit_word();
+                        self.allow_typed_word();
+                    }
+                    self.typed.push(c);
+                }
+                if !text.chars().all(is_word_char) {
+                    self.update_typed_language();
+                }
+            }
+            Event::Key {
+                key: egui::Key::Backspace,
+                pressed: true,
+                modifiers: Modifiers::NONE,
+                ..
+            } => {
+                self.typed.pop();
+            }
+            // Enter submits the field, other keys move away from the word being typed.
+            Event::Key {
+                key: egui::Key::Enter,
+                pressed: true,
+                ..
+            } => self.commit_field(),
+            Event::Key { pressed: true, .. } => {
+                self.commit_word();
+                self.typed.clear();
+            }
+            Event::Paste(_) | Event::Cut => self.typed.clear(),
+            _ => {}
+        }
+        let excess = self.typed.chars().count().saturating_sub(TYPED_TEXT_LIMIT);
+        if excess > 0 {
+            let start = self
+                .typed
+                .char_indices()
+                .nth(excess)
+                .map_or(self.typed.len(), |(i, _)| i);
+            self.typed.drain(..start);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Animating the switch between the letters, the symbols and the emojis.
+
+use crate::Keyboard;
+use egui::emath::easing;
+use egui::{vec2, Context, Vec2};
+
+/// How far the keys of the new layer slide in from, relative to the width of the keyboard.
+const SLIDE_DISTANCE_FRACTION: f32 = 0.3;
+
+/// How the keys change when switching between the letters, the symbols and the emojis, see
+/// [`Keyboard::layer_transition`]. Switching between the lower and the upper case is never
+/// animated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LayerTransition {
+    /// The new keys replace the old ones at once.
+    #[default]
+    None,
+    /// The new keys fade in.
+    Fade,
+    /// The new keys fade in while sliding in from the right when going to the symbols or the
+    /// emojis, and from the left when coming back.
+    Slide,
+}
+
+impl Keyboard {
+    /// Animate switching between the letters, the symbols and the emojis, taking as long as
+    /// [`Keyboard::show_animation`]. The keys are swapped at once without the show animation.
+    pub fn layer_transition(mut self, transition: LayerTransition) -> Self {
+        self.layer_transition = transition;
+        self
+    }
+
+    /// Which of the letters, the symbols and the emojis are shown, in the order they slide in.
+    fn layer_kind(&self) -> u8 {
+        match (self.layer.emoji, self.layer.special) {
+            (true, _) => 2,
+            (false, true) => 1,
+            (false, false) => 0,
+        }
+    }
+
+    /// Start the transition when the keys to show are of another kind than the last shown ones.
+    pub(crate) fn update_layer_transition(&mut self, ctx: &Context) {
+        let kind = self.layer_kind();
+        let previous = self.shown_layer_kind.replace(kind);
+        let (Some(previous), Some(_)) = (previous, self.show_animation) else {
+            return;
+        };
+        if previous != kind && self.layer_transition != LayerTransition::None {
+            let direction = if kind > previous { 1.0 } else { -1.0 };
+            self.layer_transition_start = Some((self.frame_time, direction));
+        }
+        if self.layer_transition_start.is_some() {
+            ctx.request_repaint();
+        }
+    }
+
+    /// Opacity and offset of the keys in this frame of the transition, for a keyboard of given
+    /// `width`.
+    pub(crate) fn layer_transition_effect(&mut self, width: f32) -> (f32, Vec2) {
+        let (Some((start, direction)), Some(duration)) =
+            (self.layer_transition_start, self.show_animation)
+        else {
+            return (1.0, Vec2::ZERO);
+        };
+        let progress = ((self.frame_time - start) as f32 / duration.max(f32::EPSILON)).min(1.0);
+        if progress >= 1.0 {
+            self.layer_transition_start = None;
+        }
+        let progress = easing::cubic_out(progress);
+        let offset = match self.layer_transition {
+            LayerTransition::Slide => vec2(
+                (1.0 - progress) * direction * SLIDE_DISTANCE_FRACTION * width,
+                0.0,
+            ),
+            LayerTransition::None | LayerTransition::Fade => Vec2::ZERO,
+        };
+        (progress, offset)
+    }
+}
@@ -0,0 +1,31 @@
+use crate::layouts::Layer;
+
+/// Settings applying only to a particular widget, see [`crate::Keyboard::widget_config`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WidgetConfig {
+    /// Layer shown when the widget gets focus for the first time, e.g. the special one for
+    /// a formula field. Lowercase letters are shown if not set. Afterwards, the keyboard comes
+    /// back to whatever layer the user left the widget with.
+    pub starting_layer: Option<Layer>,
+
+    /// What is typed into the widget, adapting the keys to it.
+    pub input_hint: InputHint,
+}
+
+/// What is typed into a widget, see [`crate::Keyboard::input_hint`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum InputHint {
+    /// Any text, typed with the layout of the keyboard.
+    #[default]
+    Text,
+    /// Numbers, typed with a numeric pad instead of the layout.
+    Numeric,
+    /// Email address, with the `@` and `.` keys next to the space key.
+    Email,
+    /// Web address, with the `/` and `.` keys next to the space key.
+    Url,
+    /// Password, which is never pasted from the clipboard chip, so it does not offer its
+    /// content.
+    Password,
+}